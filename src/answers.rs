@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+
+/// Verified puzzle answers, stored in TOML as one table per day:
+///
+/// ```toml
+/// [2015.1]
+/// 1 = "280"
+/// 2 = "1797"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(usize, usize, usize), String>);
+
+fn parse_key(key: &str, what: &str) -> Result<usize, Error> {
+    key.parse::<usize>()
+        .map_err(|_| Error::msg(format!("Invalid {} '{}' in answers.", what, key)))
+}
+
+impl Answers {
+    /// Parse answers from their TOML content.
+    pub fn parse(content: &str) -> Result<Answers, Error> {
+        let mut answers: BTreeMap<(usize, usize, usize), String> = BTreeMap::new();
+        for (year, days) in content.parse::<toml::Table>()? {
+            let year: usize = parse_key(&year, "year")?;
+            let days: toml::Table = days
+                .try_into()
+                .map_err(|_| Error::msg(format!("Expected a table of days for {}.", year)))?;
+            for (day, parts) in days {
+                let day: usize = parse_key(&day, "day")?;
+                let parts: toml::Table = parts.try_into().map_err(|_| {
+                    Error::msg(format!("Expected a table of parts for {} {}.", year, day))
+                })?;
+                for (part, answer) in parts {
+                    let part: usize = parse_key(&part, "part")?;
+                    let answer: String = match answer {
+                        toml::Value::String(s) => s,
+                        toml::Value::Integer(i) => i.to_string(),
+                        _ => {
+                            return Err(Error::msg(format!(
+                                "Unsupported answer for {} {} pt{}.",
+                                year, day, part
+                            )));
+                        }
+                    };
+                    answers.insert((year, day, part), answer);
+                }
+            }
+        }
+        Ok(Answers(answers))
+    }
+
+    /// Load answers from a file.
+    pub fn load(path: &Path) -> Result<Answers, Error> {
+        Answers::parse(&read_to_string(path)?)
+            .map_err(|e| Error::msg(format!("Invalid answers {}: {}", path.display(), e)))
+    }
+
+    pub fn get(&self, year: usize, day: usize, part: usize) -> Option<&str> {
+        self.0.get(&(year, day, part)).map(|s| s.as_str())
+    }
+
+    pub fn is_verified(&self, year: usize, day: usize, part: usize) -> bool {
+        self.0.contains_key(&(year, day, part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() -> Result<(), Error> {
+        let answers: Answers =
+            Answers::parse("[2015.1]\n1 = \"280\"\n2 = 1797\n\n[2025]\n3 = { 1 = \"357\" }\n")?;
+        assert_eq!(answers.get(2015, 1, 1), Some("280"));
+        assert_eq!(answers.get(2015, 1, 2), Some("1797"));
+        assert!(answers.is_verified(2025, 3, 1));
+        assert!(!answers.is_verified(2025, 3, 2));
+        assert!(Answers::parse("[2015.first]\n1 = \"280\"\n").is_err());
+        Ok(())
+    }
+}
@@ -1,2 +1,152 @@
+use std::path::PathBuf;
+
+use anyhow::{Error, Result};
+
+use crate::catalog;
+use crate::check::InputProblem;
+use crate::io::read_lines;
+use crate::variants::{Variant, find_variant};
+
 pub mod year_2015;
+pub mod year_2019;
 pub mod year_2025;
+
+/// Solver of a part, reading the puzzle input from a file.
+pub type Solver = fn(PathBuf) -> Result<(), Error>;
+
+/// Solvers of every day of a year, as `(day, part 1, part 2)`.
+fn solvers(year: usize) -> &'static [(usize, Solver, Solver)] {
+    match year {
+        2015 => year_2015::SOLVERS,
+        2019 => year_2019::SOLVERS,
+        2025 => year_2025::SOLVERS,
+        _ => &[],
+    }
+}
+
+/// Years with at least one solved day.
+pub fn years() -> Vec<usize> {
+    catalog::years()
+        .filter(|&year| !solvers(year).is_empty())
+        .collect()
+}
+
+/// Days with a solver in a given year.
+pub fn days(year: usize) -> Vec<usize> {
+    solvers(year).iter().map(|&(day, _, _)| day).collect()
+}
+
+/// Run a specific part of a day, with a named variant if given.
+pub fn run_day(
+    year: usize,
+    day: usize,
+    part: usize,
+    variant: Option<&str>,
+    input: PathBuf,
+) -> Result<(), Error> {
+    if let Some(name) = variant {
+        let variant: &Variant = find_variant(variants(year, day), part, name)?;
+        let answer: String = (variant.solve)(&read_lines(input)?)?;
+        tracing::info!("Answer ({} variant): {}", variant.name, answer);
+        return Ok(());
+    }
+    let &(_, part_1, part_2) = solvers(year)
+        .iter()
+        .find(|&&(d, _, _)| d == day)
+        .ok_or(Error::msg(format!("No solver for {} day {}.", year, day)))?;
+    match part {
+        1 => part_1(input),
+        2 => part_2(input),
+        _ => Err(Error::msg(format!("Unsupported part {}.", part))),
+    }
+}
+
+/// Named solver variants of a day, used for differential testing.
+pub fn variants(year: usize, day: usize) -> &'static [Variant] {
+    match year {
+        2015 => year_2015::variants(day),
+        2025 => year_2025::variants(day),
+        _ => &[],
+    }
+}
+
+/// Check the shape of the input of a day, without solving it.
+pub fn check_day(year: usize, day: usize, input: PathBuf) -> Result<Vec<InputProblem>, Error> {
+    check_lines(year, day, &read_lines(input)?)
+}
+
+/// Check the shape of the input lines of a day.
+pub fn check_lines(year: usize, day: usize, lines: &[String]) -> Result<Vec<InputProblem>, Error> {
+    match year {
+        2015 => year_2015::check_day(day, lines),
+        2025 => year_2025::check_day(day, lines),
+        _ => Err(Error::msg(format!(
+            "No input check for {} day {}.",
+            year, day
+        ))),
+    }
+}
+
+/// Generate a random input for a day. The same seed always gives the same input, on any
+/// platform and with any version of `rand`.
+pub fn generate_day(year: usize, day: usize, size: usize, seed: u64) -> Result<Vec<String>, Error> {
+    match year {
+        2015 => year_2015::generate_day(day, size, seed),
+        2025 => year_2025::generate_day(day, size, seed),
+        _ => Err(Error::msg(format!(
+            "No input generator for {} day {}.",
+            year, day
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::find_disagreement;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_days() {
+        assert_eq!(years(), vec![2015, 2025]);
+        assert_eq!(days(2015), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(days(2025).len(), 7);
+        assert!(days(2016).is_empty());
+        assert!(check_lines(2015, 9, &[]).is_err());
+        assert!(check_lines(2016, 1, &[]).is_err());
+        assert!(generate_day(2019, 1, 10, 0).is_err());
+    }
+
+    #[test]
+    fn test_generated_inputs_are_valid() {
+        for year in years() {
+            for day in days(year) {
+                for seed in 0..3 {
+                    let lines: Vec<String> = generate_day(year, day, 20, seed).unwrap();
+                    assert_eq!(
+                        check_lines(year, day, &lines).unwrap(),
+                        vec![],
+                        "{} {}",
+                        year,
+                        day
+                    );
+                    assert_eq!(lines, generate_day(year, day, 20, seed).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_variants_agree() {
+        for year in years() {
+            for day in days(year) {
+                let generate =
+                    |size: usize, seed: u64| generate_day(year, day, size, seed).unwrap();
+                if let Some(disagreement) = find_disagreement(variants(year, day), generate, 32, 3)
+                {
+                    panic!("{} {}: {:?}", year, day, disagreement);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use std::path::PathBuf;
-
 use anyhow::{Error, Result};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::calendar::Solver;
+use crate::check::InputProblem;
+use crate::variants::Variant;
 
 mod day1;
 mod day2;
@@ -9,39 +13,52 @@ mod day4;
 mod day5;
 mod day6;
 
-/// Run a specific day of 2015.
-pub fn run_day(day: usize, part: usize, input: PathBuf) -> Result<(), Error> {
+/// Solvers of every day, as `(day, part 1, part 2)`.
+pub const SOLVERS: &[(usize, Solver, Solver)] = &[
+    (1, day1::run_part_1, day1::run_part_2),
+    (2, day2::run_part_1, day2::run_part_2),
+    (3, day3::run_part_1, day3::run_part_2),
+    (4, day4::run_part_1, day4::run_part_2),
+    (5, day5::run_part_1, day5::run_part_2),
+    (6, day6::run_part_1, day6::run_part_2),
+];
+
+/// Named solver variants of a specific day of 2015.
+pub fn variants(day: usize) -> &'static [Variant] {
     match day {
-        1 => match part {
-            1 => day1::run_part_1(input),
-            2 => day1::run_part_2(input),
-            _ => panic!("Unsupported part {}", part),
-        },
-        2 => match part {
-            1 => day2::run_part_1(input),
-            2 => day2::run_part_2(input),
-            _ => panic!("Unsupported part {}", part),
-        },
-        3 => match part {
-            1 => day3::run_part_1(input),
-            2 => day3::run_part_2(input),
-            _ => panic!("Unsupported part {}", part),
-        },
-        4 => match part {
-            1 => day4::run_part_1(input),
-            2 => day4::run_part_2(input),
-            _ => panic!("Unsupported part {}", part),
-        },
-        5 => match part {
-            1 => day5::run_part_1(input),
-            2 => day5::run_part_2(input),
-            _ => panic!("Unsupported part {}", part),
-        },
-        6 => match part {
-            1 => day6::run_part_1(input),
-            2 => day6::run_part_2(input),
-            _ => panic!("Unsupported part {}", part),
-        },
-        _ => panic!("Unsupported day {}", day),
+        6 => day6::VARIANTS,
+        _ => &[],
     }
 }
+
+/// Check the input of a specific day of 2015.
+pub fn check_day(day: usize, lines: &[String]) -> Result<Vec<InputProblem>, Error> {
+    Ok(match day {
+        1 => day1::check_input(lines),
+        2 => day2::check_input(lines),
+        3 => day3::check_input(lines),
+        4 => day4::check_input(lines),
+        5 => day5::check_input(lines),
+        6 => day6::check_input(lines),
+        _ => return Err(Error::msg(format!("No input check for 2015 day {}.", day))),
+    })
+}
+
+/// Generate a random input for a specific day of 2015.
+pub fn generate_day(day: usize, size: usize, seed: u64) -> Result<Vec<String>, Error> {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+    Ok(match day {
+        1 => day1::generate_input(size, &mut rng),
+        2 => day2::generate_input(size, &mut rng),
+        3 => day3::generate_input(size, &mut rng),
+        4 => day4::generate_input(size, &mut rng),
+        5 => day5::generate_input(size, &mut rng),
+        6 => day6::generate_input(size, &mut rng),
+        _ => {
+            return Err(Error::msg(format!(
+                "No input generator for 2015 day {}.",
+                day
+            )));
+        }
+    })
+}
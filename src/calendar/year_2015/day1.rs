@@ -1,17 +1,41 @@
 use anyhow::{Error, Result};
+use rand::Rng;
 use std::path::PathBuf;
 
-use crate::io::read_chars;
+use crate::check::{InputProblem, check_charset, check_single_line};
+use crate::io::read_line;
+
+/// Floor change of a single instruction.
+fn floor_change(instruction: char) -> Result<i32, Error> {
+    match instruction {
+        '(' => Ok(1),
+        ')' => Ok(-1),
+        _ => Err(Error::msg(format!(
+            "Unsupported character: {}",
+            instruction
+        ))),
+    }
+}
+
+/// Check that the input is a single line of parentheses.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_single_line(lines, |line| check_charset(line, "()"))
+}
+
+/// Generate `size` floor instructions.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    vec![
+        (0..size)
+            .map(|_| if rng.random_bool(0.5) { '(' } else { ')' })
+            .collect(),
+    ]
+}
 
 /// Find final floor based on instructions.
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
     let mut current_position: i32 = 0;
-    for instruction in read_chars(input)? {
-        match instruction {
-            '(' => current_position += 1,
-            ')' => current_position -= 1,
-            _ => panic!("Unsupported character: {}", instruction),
-        }
+    for instruction in read_line(input)?.chars() {
+        current_position += floor_change(instruction)?;
     }
     tracing::info!("Final destination: {}", current_position);
     Ok(())
@@ -20,12 +44,8 @@ pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
 /// Find first time in the basement, base-1 index.
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
     let mut current_position: i32 = 0;
-    for (pos, instruction) in read_chars(input)?.iter().enumerate() {
-        match instruction {
-            '(' => current_position += 1,
-            ')' => current_position -= 1,
-            _ => panic!("Unsupported character: {}", instruction),
-        }
+    for (pos, instruction) in read_line(input)?.chars().enumerate() {
+        current_position += floor_change(instruction)?;
         if current_position == -1 {
             // Offset 1 due to base-1 index.
             tracing::info!("Instruction index: {}", pos + 1);
@@ -34,3 +54,16 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_floor_change_never_panics(c in any::<char>()) {
+            prop_assert_eq!(floor_change(c).is_ok(), "()".contains(c));
+        }
+    }
+}
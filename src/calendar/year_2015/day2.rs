@@ -1,37 +1,72 @@
 use anyhow::{Error, Result};
-use pretty_assertions::assert_eq;
+use rand::Rng;
 use std::path::PathBuf;
 
+use crate::check::{InputProblem, check_each_line};
 use crate::io::read_lines;
+use crate::parse_fmt;
 
-/// Calculate the surface of a single box.
-fn get_box_surface(line: &str) -> Result<i32, Error> {
-    let mut total_box_surface: i32 = 0;
+/// Parse the three sides of a box, like `2x3x4`.
+fn parse_box(line: &str) -> Result<[i32; 3], Error> {
+    let (length, width, height): (i32, i32, i32) = parse_fmt!("{length}x{width}x{height}", line)?;
+    Ok([length, width, height])
+}
 
-    let sides: Vec<i32> = line
-        .split(&"x")
-        .map(|d| d.parse::<i32>().unwrap())
-        .collect();
-    assert_eq!(sides.len(), 3);
+fn box_overflow(line: &str) -> Error {
+    Error::msg(format!("Box '{}' is too large.", line))
+}
 
-    let length: &i32 = sides.first().unwrap();
-    let width: &i32 = sides.get(1).unwrap();
-    let height: &i32 = sides.get(2).unwrap();
+/// Calculate the surface of a single box.
+fn get_box_surface(line: &str) -> Result<i32, Error> {
+    let [length, width, height] = parse_box(line)?;
 
-    let prods: [i32; 3] = [length * width, *width * height, *length * height];
-    let slack: i32 = *prods.iter().min().unwrap();
+    let prods: [i32; 3] = [
+        length.checked_mul(width).ok_or(box_overflow(line))?,
+        width.checked_mul(height).ok_or(box_overflow(line))?,
+        length.checked_mul(height).ok_or(box_overflow(line))?,
+    ];
+    let slack: i32 = prods[0].min(prods[1]).min(prods[2]);
+
+    prods
+        .iter()
+        .try_fold(slack, |total, &prod| {
+            total.checked_add(prod)?.checked_add(prod)
+        })
+        .ok_or(box_overflow(line))
+}
 
-    total_box_surface += prods.iter().sum::<i32>() * 2;
-    total_box_surface += slack;
+/// Check that every line holds the three sides of a box.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_each_line(lines, |line| {
+        let sides: Vec<&str> = line.split('x').collect();
+        if sides.len() == 3 && sides.iter().all(|s| s.parse::<i32>().is_ok_and(|v| v > 0)) {
+            None
+        } else {
+            Some("expected `NxNxN`".to_string())
+        }
+    })
+}
 
-    Ok(total_box_surface)
+/// Generate `size` boxes with sides up to 30.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{}x{}x{}",
+                rng.random_range(1..=30),
+                rng.random_range(1..=30),
+                rng.random_range(1..=30)
+            )
+        })
+        .collect()
 }
 
 // Find total wrapping paper area.
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
     let mut total_surface: i32 = 0;
-    for line in read_lines(input)? {
-        total_surface += get_box_surface(&line)?;
+    for (index, line) in read_lines(input)?.iter().enumerate() {
+        total_surface += get_box_surface(line)
+            .map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))?;
     }
     tracing::info!("Total surface: {}", total_surface);
     Ok(())
@@ -39,28 +74,27 @@ pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
 
 /// Calculate the smalles box face perimeter.
 fn get_smallest_box_face_perimeter(line: &str) -> Result<i32, Error> {
-    let mut sides_vec: Vec<i32> = line
-        .split(&"x")
-        .map(|d| d.parse::<i32>().unwrap())
-        .collect();
-    assert_eq!(sides_vec.len(), 3);
-    sides_vec.sort();
-
-    let sides: [i32; 3] = [
-        *sides_vec.first().unwrap(),
-        *sides_vec.get(1).unwrap(),
-        *sides_vec.get(2).unwrap(),
-    ];
-    let wrap_length: i32 = 2 * sides[0] + 2 * sides[1];
-    let ribbon_length: i32 = sides.iter().product::<i32>();
-
-    Ok(wrap_length + ribbon_length)
+    let mut sides: [i32; 3] = parse_box(line)?;
+    sides.sort();
+
+    let wrap_length: Option<i32> = sides[0]
+        .checked_add(sides[1])
+        .and_then(|s| s.checked_mul(2));
+    let ribbon_length: Option<i32> = sides[0]
+        .checked_mul(sides[1])
+        .and_then(|p| p.checked_mul(sides[2]));
+
+    wrap_length
+        .zip(ribbon_length)
+        .and_then(|(wrap, ribbon)| wrap.checked_add(ribbon))
+        .ok_or(box_overflow(line))
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
     let mut total_length: i32 = 0;
-    for line in read_lines(input)? {
-        total_length += get_smallest_box_face_perimeter(&line)?;
+    for (index, line) in read_lines(input)?.iter().enumerate() {
+        total_length += get_smallest_box_face_perimeter(line)
+            .map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))?;
     }
     tracing::info!("Total length: {}", total_length);
     Ok(())
@@ -71,16 +105,40 @@ mod tests {
     use super::*;
 
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_get_box_surface() {
-        assert_eq!(get_box_surface(&"2x3x4").unwrap(), 58);
-        assert_eq!(get_box_surface(&"1x1x10").unwrap(), 43);
+        assert_eq!(get_box_surface("2x3x4").unwrap(), 58);
+        assert_eq!(get_box_surface("1x1x10").unwrap(), 43);
     }
 
     #[test]
     fn test_ribbon_feet_for_box() {
-        assert_eq!(get_smallest_box_face_perimeter(&"2x3x4").unwrap(), 34);
-        assert_eq!(get_smallest_box_face_perimeter(&"1x1x10").unwrap(), 14);
+        assert_eq!(get_smallest_box_face_perimeter("2x3x4").unwrap(), 34);
+        assert_eq!(get_smallest_box_face_perimeter("1x1x10").unwrap(), 14);
+    }
+
+    #[test]
+    fn test_check_input() {
+        assert_eq!(check_input(&["2x3x4".to_string()]), vec![]);
+        assert_eq!(
+            check_input(&["2x3x4".to_string(), "2x3".to_string()]),
+            vec![InputProblem::at(1, "expected `NxNxN`")]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_parsers_never_panic(line in "\\PC*") {
+            let _ = get_box_surface(&line);
+            let _ = get_smallest_box_face_perimeter(&line);
+        }
+
+        #[test]
+        fn test_parsers_never_panic_near_valid(line in "-?[0-9]{1,11}(x-?[0-9]{0,11}){0,3}") {
+            let _ = get_box_surface(&line);
+            let _ = get_smallest_box_face_perimeter(&line);
+        }
     }
 }
@@ -1,93 +1,55 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashSet, path::PathBuf};
 
 use anyhow::{Error, Result};
+use rand::Rng;
 
-use crate::io::read;
+use crate::check::{InputProblem, check_charset, check_single_line};
+use crate::io::read_line;
+use crate::point::{Direction, Point2};
 
-// Count visited houses.
-fn count_visited_houses(path: &str) -> Result<usize, Error> {
-    let mut visited_houses: HashMap<[i32; 2], i32> = HashMap::new();
-    let mut current_position: [i32; 2] = [0, 0];
+/// Count the houses visited by santas taking turns on the moves.
+fn count_houses_visited_by(path: &str, num_santas: usize) -> Result<usize, Error> {
+    let mut visited_houses: HashSet<Point2> = HashSet::from([Point2::ORIGIN]);
+    let mut positions: Vec<Point2> = vec![Point2::ORIGIN; num_santas];
 
-    match visited_houses.get(&current_position) {
-        Some(v) => visited_houses.insert(current_position, v + 1),
-        None => visited_houses.insert(current_position, 1),
-    };
-
-    for c in path.chars() {
-        match c {
-            '>' => current_position[0] += 1,
-            '^' => current_position[1] += 1,
-            '<' => current_position[0] -= 1,
-            'v' => current_position[1] -= 1,
-            _ => panic!("Unsupported char: '{}'", c),
-        }
-
-        match visited_houses.get(&current_position) {
-            Some(v) => visited_houses.insert(current_position, v + 1),
-            None => visited_houses.insert(current_position, 1),
-        };
+    for (i, c) in path.chars().enumerate() {
+        let position: &mut Point2 = &mut positions[i % num_santas];
+        *position += Direction::from_arrow(c)?;
+        visited_houses.insert(*position);
     }
 
     Ok(visited_houses.len())
 }
 
+// Count visited houses.
+fn count_visited_houses(path: &str) -> Result<usize, Error> {
+    count_houses_visited_by(path, 1)
+}
+
+/// Check that the input is a single line of moves.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_single_line(lines, |line| check_charset(line, "^>v<"))
+}
+
+/// Generate `size` moves.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    let moves: [char; 4] = ['^', '>', 'v', '<'];
+    vec![(0..size).map(|_| moves[rng.random_range(0..4)]).collect()]
+}
+
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let num_visited_houses: usize = count_visited_houses(&read(input)?)?;
+    let num_visited_houses: usize = count_visited_houses(&read_line(input)?)?;
     tracing::info!("Visited houses: {}", num_visited_houses);
     Ok(())
 }
 
 // Count visited houses with RoboSanta's help.
 fn count_visited_houses_with_robosanta(path: &str) -> Result<usize, Error> {
-    let mut visited_houses: HashMap<[i32; 2], i32> = HashMap::new();
-    let mut current_position: [i32; 2] = [0, 0];
-    let mut current_position_robo: [i32; 2] = [0, 0];
-
-    match visited_houses.get(&current_position) {
-        Some(v) => visited_houses.insert(current_position, v + 1),
-        None => visited_houses.insert(current_position, 1),
-    };
-
-    for (i, c) in path.chars().enumerate() {
-        match i % 2 {
-            0 => {
-                match c {
-                    '>' => current_position[0] += 1,
-                    '^' => current_position[1] += 1,
-                    '<' => current_position[0] -= 1,
-                    'v' => current_position[1] -= 1,
-                    _ => panic!("Unsupported char: '{}'", c),
-                }
-
-                match visited_houses.get(&current_position) {
-                    Some(v) => visited_houses.insert(current_position, v + 1),
-                    None => visited_houses.insert(current_position, 1),
-                };
-            }
-            1 => {
-                match c {
-                    '>' => current_position_robo[0] += 1,
-                    '^' => current_position_robo[1] += 1,
-                    '<' => current_position_robo[0] -= 1,
-                    'v' => current_position_robo[1] -= 1,
-                    _ => panic!("Unsupported char: '{}'", c),
-                }
-
-                match visited_houses.get(&current_position_robo) {
-                    Some(v) => visited_houses.insert(current_position_robo, v + 1),
-                    None => visited_houses.insert(current_position_robo, 1),
-                };
-            }
-            _ => panic!("Unreachable code."),
-        };
-    }
-
-    Ok(visited_houses.len())
+    count_houses_visited_by(path, 2)
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let num_visited_houses: usize = count_visited_houses_with_robosanta(&read(input)?)?;
+    let num_visited_houses: usize = count_visited_houses_with_robosanta(&read_line(input)?)?;
     tracing::info!("Visited houses with RoboSanta: {}", num_visited_houses);
     Ok(())
 }
@@ -96,21 +58,31 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_visited_houses() {
-        assert_eq!(count_visited_houses(&">").unwrap(), 2);
-        assert_eq!(count_visited_houses(&"^>v<").unwrap(), 4);
-        assert_eq!(count_visited_houses(&"^v^v^v^v^v").unwrap(), 2);
+        assert_eq!(count_visited_houses(">").unwrap(), 2);
+        assert_eq!(count_visited_houses("^>v<").unwrap(), 4);
+        assert_eq!(count_visited_houses("^v^v^v^v^v").unwrap(), 2);
     }
 
     #[test]
     fn test_visited_houses_with_robosanta() {
-        assert_eq!(count_visited_houses_with_robosanta(&"^v").unwrap(), 3);
-        assert_eq!(count_visited_houses_with_robosanta(&"^>v<").unwrap(), 3);
+        assert_eq!(count_visited_houses_with_robosanta("^v").unwrap(), 3);
+        assert_eq!(count_visited_houses_with_robosanta("^>v<").unwrap(), 3);
         assert_eq!(
-            count_visited_houses_with_robosanta(&"^v^v^v^v^v").unwrap(),
+            count_visited_houses_with_robosanta("^v^v^v^v^v").unwrap(),
             11
         );
     }
+
+    proptest! {
+        #[test]
+        fn test_parsers_never_panic(path in "\\PC*") {
+            let is_valid: bool = path.chars().all(|c| "^>v<".contains(c));
+            prop_assert_eq!(count_visited_houses(&path).is_ok(), is_valid);
+            prop_assert_eq!(count_visited_houses_with_robosanta(&path).is_ok(), is_valid);
+        }
+    }
 }
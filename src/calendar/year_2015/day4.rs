@@ -1,43 +1,67 @@
 use std::path::PathBuf;
 
 use anyhow::{Error, Result};
+use rand::Rng;
 
-use crate::io::read;
+use crate::check::{InputProblem, check_single_line};
+use crate::hashing::{find_first_nonce, has_leading_zeros};
+use crate::io::read_line;
 
-fn get_lowest_integer(secret_key: &str, starts_with: &str) -> Result<u32, Error> {
-    let mut counter: u32 = 0;
-    loop {
-        let digest: md5::Digest = md5::compute(format!("{}{}", secret_key, counter));
+/// Lowest counter whose hash with the secret key starts with `zeros` hex zeros.
+fn get_lowest_integer(secret_key: &str, zeros: usize) -> Result<u64, Error> {
+    if zeros > 32 {
+        return Err(Error::msg(format!(
+            "An MD5 hash has 32 hex digits, cannot start with {} zeros.",
+            zeros
+        )));
+    }
+    find_first_nonce(secret_key, |digest| has_leading_zeros(digest, zeros)).ok_or(Error::msg(
+        format!("No counter gives a hash starting with {} zeros.", zeros),
+    ))
+}
 
-        if format!("{:x}", digest).starts_with(starts_with) {
-            break;
+/// Check that the input is a single secret key.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_single_line(lines, |line| {
+        if line.is_empty() {
+            Some("expected a secret key".to_string())
+        } else {
+            None
         }
+    })
+}
 
-        counter += 1;
-    }
-    Ok(counter)
+/// Generate a lowercase secret key of `size` characters.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    vec![
+        (0..size.max(1))
+            .map(|_| char::from(rng.random_range(b'a'..=b'z')))
+            .collect(),
+    ]
 }
 
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let counter: u32 = get_lowest_integer(&read(input)?, "00000")?;
+    let counter: u64 = get_lowest_integer(&read_line(input)?, 5)?;
     tracing::info!("{}", counter);
     Ok(())
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let counter: u32 = get_lowest_integer(&read(input)?, "000000")?;
+    let counter: u64 = get_lowest_integer(&read_line(input)?, 6)?;
     tracing::info!("{}", counter);
     Ok(())
 }
 
-// #[cfg(test)]
-// mod test {
-//     use super::*;
-//     use pretty_assertions::assert_eq;
-
-//     #[test]
-//     fn test_get_lowest_integer() {
-//         assert_eq!(get_lowest_integer(&"abcdef", "00000").unwrap(), 609043);
-//         assert_eq!(get_lowest_integer(&"pqrstuv", "00000").unwrap(), 1048970);
-//     }
-// }
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_get_lowest_integer() -> Result<(), Error> {
+        assert_eq!(get_lowest_integer("abcdef", 5)?, 609043);
+        assert_eq!(get_lowest_integer("pqrstuv", 5)?, 1048970);
+        assert!(get_lowest_integer("abcdef", 33).is_err());
+        Ok(())
+    }
+}
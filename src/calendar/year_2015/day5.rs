@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use anyhow::{Error, Result};
+use rand::Rng;
 
+use crate::check::{InputProblem, check_charset, check_each_line};
 use crate::io::read_lines;
 
 fn is_char_vowel(c: &char) -> bool {
@@ -14,7 +16,7 @@ fn is_string_nice(s: &str) -> Result<bool, Error> {
     let blacklist: [&str; 4] = ["ab", "cd", "pq", "xy"];
     let mut found_blacklist: bool = false;
 
-    let mut previous_char: char = s.chars().next().unwrap();
+    let mut previous_char: char = s.chars().next().ok_or(Error::msg("Empty string."))?;
     if is_char_vowel(&previous_char) {
         vowel_counter += 1;
     }
@@ -37,6 +39,28 @@ fn is_string_nice(s: &str) -> Result<bool, Error> {
     Ok((vowel_counter >= 3) & found_dimer & (!found_blacklist))
 }
 
+/// Check that every line is a non-empty lowercase string.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_each_line(lines, |line| {
+        if line.is_empty() {
+            Some("expected a string".to_string())
+        } else {
+            check_charset(line, "abcdefghijklmnopqrstuvwxyz")
+        }
+    })
+}
+
+/// Generate `size` strings of 16 lowercase letters.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            (0..16)
+                .map(|_| char::from(rng.random_range(b'a'..=b'z')))
+                .collect()
+        })
+        .collect()
+}
+
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
     let mut nice_string_counter: usize = 0;
     for l in read_lines(input)? {
@@ -49,13 +73,16 @@ pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
 }
 
 fn is_string_nice_v2(s: &str) -> Result<bool, Error> {
+    if !s.is_ascii() {
+        return Err(Error::msg(format!("Non-ASCII string: {}", s)));
+    }
     let mut found_repeated_nonoverlapping_dimer: bool = false;
     let mut found_trimer: bool = false;
     for (p, c) in s.char_indices() {
         if p + 1 < s.len() && s[(p + 2)..].contains(&s[p..(p + 2)]) {
             found_repeated_nonoverlapping_dimer = true;
         }
-        if p + 2 < s.len() && c == s[(p + 2)..].chars().next().unwrap() {
+        if p + 2 < s.len() && s[(p + 2)..].starts_with(c) {
             found_trimer = true;
         }
         if found_repeated_nonoverlapping_dimer & found_trimer {
@@ -80,21 +107,30 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_is_string_nice() {
-        assert_eq!(is_string_nice(&"ugknbfddgicrmopn").unwrap(), true);
-        assert_eq!(is_string_nice(&"aaa").unwrap(), true);
-        assert_eq!(is_string_nice(&"jchzalrnumimnmhp").unwrap(), false);
-        assert_eq!(is_string_nice(&"haegwjzuvuyypxyu").unwrap(), false);
-        assert_eq!(is_string_nice(&"dvszwmarrgswjxmb").unwrap(), false);
+        assert_eq!(is_string_nice("ugknbfddgicrmopn").unwrap(), true);
+        assert_eq!(is_string_nice("aaa").unwrap(), true);
+        assert_eq!(is_string_nice("jchzalrnumimnmhp").unwrap(), false);
+        assert_eq!(is_string_nice("haegwjzuvuyypxyu").unwrap(), false);
+        assert_eq!(is_string_nice("dvszwmarrgswjxmb").unwrap(), false);
     }
 
     #[test]
     fn test_is_string_nice_v2() {
-        assert_eq!(is_string_nice_v2(&"qjhvhtzxzqqjkmpb").unwrap(), true);
-        assert_eq!(is_string_nice_v2(&"xxyxx").unwrap(), true);
-        assert_eq!(is_string_nice_v2(&"uurcxstgmygtbstg").unwrap(), false);
-        assert_eq!(is_string_nice_v2(&"ieodomkazucvgmuy").unwrap(), false);
+        assert_eq!(is_string_nice_v2("qjhvhtzxzqqjkmpb").unwrap(), true);
+        assert_eq!(is_string_nice_v2("xxyxx").unwrap(), true);
+        assert_eq!(is_string_nice_v2("uurcxstgmygtbstg").unwrap(), false);
+        assert_eq!(is_string_nice_v2("ieodomkazucvgmuy").unwrap(), false);
+    }
+
+    proptest! {
+        #[test]
+        fn test_parsers_never_panic(s in "\\PC*") {
+            prop_assert_eq!(is_string_nice(&s).is_ok(), !s.is_empty());
+            prop_assert_eq!(is_string_nice_v2(&s).is_ok(), s.is_ascii());
+        }
     }
 }
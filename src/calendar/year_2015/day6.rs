@@ -1,43 +1,56 @@
 use std::path::PathBuf;
 
+use std::str::FromStr;
+
 use anyhow::{Error, Result};
-use ndarray::{Array2, s};
-use pretty_assertions::assert_eq;
+use ndarray::s;
+use rand::Rng;
 
+use crate::check::{InputProblem, check_each_line};
+use crate::grid::Grid;
 use crate::io::read_lines;
+use crate::parse_fmt;
+use crate::rectangles::{Rectangle, apply_compressed};
+use crate::variants::Variant;
+
+/// Side of the square grid of lights.
+const GRID_SIZE: usize = 1000;
 
-fn count_on_lights(grid: &Array2<bool>) -> usize {
+fn count_on_lights(grid: &Grid<bool>) -> usize {
     grid.iter().filter(|&&v| v).count()
 }
 
 fn execute_single_light_operation(
-    mut grid: Array2<bool>,
+    mut grid: Grid<bool>,
     instruction: &ParsedInstruction,
-) -> Result<Array2<bool>, Error> {
+) -> Result<Grid<bool>, Error> {
     match instruction.op {
         OperationType::On => {
             // Turn on the lights.
-            grid.slice_mut(s![
-                instruction.xmin..(instruction.xmax + 1),
-                instruction.ymin..(instruction.ymax + 1)
-            ])
-            .mapv_inplace(|_| true);
+            grid.view_mut()
+                .slice_mut(s![
+                    instruction.xmin..(instruction.xmax + 1),
+                    instruction.ymin..(instruction.ymax + 1)
+                ])
+                .mapv_inplace(|_| true);
         }
         OperationType::Off => {
             // Turn off the lights.
-            grid.slice_mut(s![
-                instruction.xmin..(instruction.xmax + 1),
-                instruction.ymin..(instruction.ymax + 1)
-            ])
-            .mapv_inplace(|_| false);
+            grid.view_mut()
+                .slice_mut(s![
+                    instruction.xmin..(instruction.xmax + 1),
+                    instruction.ymin..(instruction.ymax + 1)
+                ])
+                .mapv_inplace(|_| false);
         }
         OperationType::Toggle => {
             // Toggle the lights.
-            grid.slice_mut(s![
-                instruction.xmin..(instruction.xmax + 1),
-                instruction.ymin..(instruction.ymax + 1)
-            ])
-            .mapv_inplace(|v| !v);
+            grid.view_mut()
+                .slice_mut(s![
+                    instruction.xmin..(instruction.xmax + 1),
+                    instruction.ymin..(instruction.ymax + 1)
+                ])
+                .mapv_inplace(|v| !v);
         }
     }
     Ok(grid)
@@ -60,125 +73,252 @@ struct ParsedInstruction {
     pub ymax: usize,
 }
 
-/// Parse an instruction from string to struct.
-fn parse_instruction(s: &str) -> Result<ParsedInstruction, Error> {
-    let parts: Vec<&str> = s.split(" ").collect();
-
-    let op: OperationType;
-    let p_parts_indices: [usize; 2];
-    match parts.len() {
-        4 => {
-            op = OperationType::Toggle;
-            p_parts_indices = [1, 3];
-        }
-        5 => {
-            op = if parts.get(1).unwrap() == &"on" {
-                OperationType::On
-            } else {
-                OperationType::Off
-            };
-            p_parts_indices = [2, 4];
+impl FromStr for OperationType {
+    type Err = Error;
+
+    /// Parse the switch of a `turn` instruction.
+    fn from_str(s: &str) -> Result<OperationType, Error> {
+        match s {
+            "on" => Ok(OperationType::On),
+            "off" => Ok(OperationType::Off),
+            _ => Err(Error::msg("expected `on` or `off`")),
         }
-        _ => panic!("Unsupported string: {}", s),
     }
+}
 
-    let p1: Vec<usize> = parts
-        .get(p_parts_indices[0])
-        .unwrap()
-        .split(",")
-        .map(|c| c.parse::<usize>().unwrap())
-        .collect();
-    assert_eq!(p1.len(), 2);
-
-    let p2: Vec<usize> = parts
-        .get(p_parts_indices[1])
-        .unwrap()
-        .split(",")
-        .map(|c| c.parse::<usize>().unwrap())
-        .collect();
-    assert_eq!(p2.len(), 2);
-
-    assert!(p1[0] <= p2[0]);
-    assert!(p1[1] <= p2[1]);
+/// Parse an instruction from string to struct, with no bound on the coordinates.
+fn parse_instruction(s: &str) -> Result<ParsedInstruction, Error> {
+    let (op, xmin, ymin, xmax, ymax): (OperationType, usize, usize, usize, usize) = match s
+        .strip_prefix("toggle ")
+    {
+        Some(rectangle) => {
+            let (xmin, ymin, xmax, ymax) = parse_fmt!("{x1},{y1} through {x2},{y2}", rectangle)?;
+            (OperationType::Toggle, xmin, ymin, xmax, ymax)
+        }
+        None => parse_fmt!("turn {op} {x1},{y1} through {x2},{y2}", s)?,
+    };
+    if xmin > xmax || ymin > ymax {
+        return Err(Error::msg(format!("Corners out of order: {}", s)));
+    }
 
     Ok(ParsedInstruction {
         op,
-        xmin: p1[0],
-        xmax: p2[0],
-        ymin: p1[1],
-        ymax: p2[1],
+        xmin,
+        xmax,
+        ymin,
+        ymax,
     })
 }
 
-fn operate_lights(instructions: Vec<String>) -> Result<Array2<bool>, Error> {
-    let mut grid: Array2<bool> = Array2::zeros((1000, 1000)).mapv(|_: usize| false);
-    for line in instructions {
-        grid = execute_single_light_operation(grid, &parse_instruction(&line)?)?;
+/// Parse an instruction on a rectangle of the 1000x1000 grid.
+fn parse_grid_instruction(s: &str) -> Result<ParsedInstruction, Error> {
+    let instruction: ParsedInstruction = parse_instruction(s)?;
+    if instruction.xmax >= GRID_SIZE || instruction.ymax >= GRID_SIZE {
+        return Err(Error::msg(format!("Outside of the grid: {}", s)));
+    }
+    Ok(instruction)
+}
+
+fn operate_lights(instructions: Vec<String>) -> Result<Grid<bool>, Error> {
+    let mut grid: Grid<bool> = Grid::from_elem(GRID_SIZE, GRID_SIZE, false);
+    for (index, line) in instructions.iter().enumerate() {
+        let instruction: ParsedInstruction = parse_grid_instruction(line)
+            .map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))?;
+        grid = execute_single_light_operation(grid, &instruction)?;
     }
     Ok(grid)
 }
 
+/// Apply the instructions with the coordinate-compressed engine, on a grid of any size.
+fn operate_lights_compressed<T: Copy>(
+    instructions: &[String],
+    initial: T,
+    apply: fn(T, &OperationType) -> T,
+) -> Result<u128, Error>
+where
+    u128: From<T>,
+{
+    let operations: Vec<(Rectangle, OperationType)> = instructions
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let instruction: ParsedInstruction = parse_instruction(line)
+                .map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))?;
+            let rectangle: Rectangle = Rectangle {
+                xmin: instruction.xmin,
+                xmax: instruction.xmax,
+                ymin: instruction.ymin,
+                ymax: instruction.ymax,
+            };
+            Ok((rectangle, instruction.op))
+        })
+        .collect::<Result<_, Error>>()?;
+    apply_compressed(&operations, initial, apply, u128::from)
+}
+
+/// Number of lights lit, on a grid of any size.
+fn count_on_lights_compressed(instructions: &[String]) -> Result<u128, Error> {
+    operate_lights_compressed(instructions, false, |lit, op| match op {
+        OperationType::On => true,
+        OperationType::Off => false,
+        OperationType::Toggle => !lit,
+    })
+}
+
+/// Total brightness, on a grid of any size.
+fn total_brightness_compressed(instructions: &[String]) -> Result<u128, Error> {
+    operate_lights_compressed(instructions, 0u64, |brightness, op| match op {
+        OperationType::On => brightness + 1,
+        OperationType::Off => brightness.saturating_sub(1),
+        OperationType::Toggle => brightness + 2,
+    })
+}
+
+fn check_corner(corner: &str) -> Option<(usize, usize)> {
+    let (x, y) = corner.split_once(',')?;
+    Some((x.parse::<usize>().ok()?, y.parse::<usize>().ok()?))
+}
+
+/// Check that every line is an instruction on a rectangle of the 1000x1000 grid.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_each_line(lines, |line| {
+        let Some(rectangle) = ["turn on ", "turn off ", "toggle "]
+            .iter()
+            .find_map(|op| line.strip_prefix(op))
+        else {
+            return Some("expected `turn on`, `turn off` or `toggle`".to_string());
+        };
+        let corners: Vec<Option<(usize, usize)>> =
+            rectangle.split(" through ").map(check_corner).collect();
+        match corners[..] {
+            [Some((x1, y1)), Some((x2, y2))] => {
+                if x1 > x2 || y1 > y2 {
+                    Some("expected the top-left corner first".to_string())
+                } else if x2 >= 1000 || y2 >= 1000 {
+                    Some("coordinates must be below 1000".to_string())
+                } else {
+                    None
+                }
+            }
+            _ => Some("expected `X,Y through X,Y`".to_string()),
+        }
+    })
+}
+
+/// Generate `size` instructions on random rectangles of the 1000x1000 grid.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let op: &str = ["turn on", "turn off", "toggle"][rng.random_range(0..3)];
+            let (x1, x2): (usize, usize) = (rng.random_range(0..1000), rng.random_range(0..1000));
+            let (y1, y2): (usize, usize) = (rng.random_range(0..1000), rng.random_range(0..1000));
+            format!(
+                "{} {},{} through {},{}",
+                op,
+                x1.min(x2),
+                y1.min(y2),
+                x1.max(x2),
+                y1.max(y2)
+            )
+        })
+        .collect()
+}
+
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let grid: Array2<bool> = operate_lights(read_lines(input)?)?;
+    let grid: Grid<bool> = operate_lights(read_lines(input)?)?;
     tracing::info!("{} lights are lit.", count_on_lights(&grid));
     Ok(())
 }
 
 fn execute_single_light_operation_v2(
-    mut grid: Array2<u32>,
+    mut grid: Grid<u32>,
     instruction: &ParsedInstruction,
-) -> Result<Array2<u32>, Error> {
+) -> Result<Grid<u32>, Error> {
     match instruction.op {
         OperationType::On => {
             // Turn on the lights.
-            grid.slice_mut(s![
-                instruction.xmin..(instruction.xmax + 1),
-                instruction.ymin..(instruction.ymax + 1)
-            ])
-            .mapv_inplace(|v: u32| v + 1);
+            grid.view_mut()
+                .slice_mut(s![
+                    instruction.xmin..(instruction.xmax + 1),
+                    instruction.ymin..(instruction.ymax + 1)
+                ])
+                .mapv_inplace(|v: u32| v + 1);
         }
         OperationType::Off => {
             // Turn off the lights.
-            grid.slice_mut(s![
-                instruction.xmin..(instruction.xmax + 1),
-                instruction.ymin..(instruction.ymax + 1)
-            ])
-            .mapv_inplace(|v: u32| if v == 0 { v } else { v - 1 });
+            grid.view_mut()
+                .slice_mut(s![
+                    instruction.xmin..(instruction.xmax + 1),
+                    instruction.ymin..(instruction.ymax + 1)
+                ])
+                .mapv_inplace(|v: u32| if v == 0 { v } else { v - 1 });
         }
         OperationType::Toggle => {
             // Toggle the lights.
-            grid.slice_mut(s![
-                instruction.xmin..(instruction.xmax + 1),
-                instruction.ymin..(instruction.ymax + 1)
-            ])
-            .mapv_inplace(|v| v + 2);
+            grid.view_mut()
+                .slice_mut(s![
+                    instruction.xmin..(instruction.xmax + 1),
+                    instruction.ymin..(instruction.ymax + 1)
+                ])
+                .mapv_inplace(|v| v + 2);
         }
     }
     Ok(grid)
 }
 
-fn operate_lights_v2(instructions: Vec<String>) -> Result<Array2<u32>, Error> {
-    let mut grid: Array2<u32> = Array2::zeros((1000, 1000)).mapv(|_: u32| 0);
-    for line in instructions {
-        grid = execute_single_light_operation_v2(grid, &parse_instruction(&line)?)?;
+fn operate_lights_v2(instructions: Vec<String>) -> Result<Grid<u32>, Error> {
+    let mut grid: Grid<u32> = Grid::from_elem(GRID_SIZE, GRID_SIZE, 0);
+    for (index, line) in instructions.iter().enumerate() {
+        let instruction: ParsedInstruction = parse_grid_instruction(line)
+            .map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))?;
+        grid = execute_single_light_operation_v2(grid, &instruction)?;
     }
     Ok(grid)
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let grid: Array2<u32> = operate_lights_v2(read_lines(input)?)?;
-    tracing::info!("Total brightness: {}", &grid.sum());
+    let grid: Grid<u32> = operate_lights_v2(read_lines(input)?)?;
+    tracing::info!("Total brightness: {}", grid.iter().sum::<u32>());
     Ok(())
 }
 
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "array",
+        part: 1,
+        solve: |lines| Ok(count_on_lights(&operate_lights(lines.to_vec())?).to_string()),
+    },
+    Variant {
+        name: "compressed",
+        part: 1,
+        solve: |lines| Ok(count_on_lights_compressed(lines)?.to_string()),
+    },
+    Variant {
+        name: "array",
+        part: 2,
+        solve: |lines| {
+            Ok(operate_lights_v2(lines.to_vec())?
+                .iter()
+                .sum::<u32>()
+                .to_string())
+        },
+    },
+    Variant {
+        name: "compressed",
+        part: 2,
+        solve: |lines| Ok(total_brightness_compressed(lines)?.to_string()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_execute_single_light_operation() {
-        let mut grid: Array2<bool> = Array2::zeros((1000, 1000)).mapv(|_: usize| false);
+        let mut grid: Grid<bool> = Grid::from_elem(1000, 1000, false);
         grid = execute_single_light_operation(
             grid,
             &parse_instruction("turn on 0,0 through 999,999").unwrap(),
@@ -187,6 +327,28 @@ mod tests {
         assert_eq!(count_on_lights(&grid), 1000000);
     }
 
+    #[test]
+    fn test_compressed_large_grid() -> Result<(), Error> {
+        let instructions: Vec<String> = [
+            "turn on 0,0 through 999999,999999",
+            "toggle 1000,1000 through 1999999,1000",
+            "turn off 500000,0 through 500000,999999",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            count_on_lights_compressed(&instructions)?,
+            1_000_000 * 1_000_000 - 999_000 + 1_000_000 - 999_999
+        );
+        assert_eq!(
+            total_brightness_compressed(&instructions)?,
+            1_000_000 * 1_000_000 + 2 * 1_999_000 - 1_000_000
+        );
+        assert!(parse_grid_instruction("toggle 1000,1000 through 1999999,1000").is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_parse_instruction() {
         assert_eq!(
@@ -220,4 +382,35 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_check_input() {
+        assert_eq!(
+            check_input(&[
+                "turn on 0,0 through 999,999".to_string(),
+                "turn of 0,0 through 999,999".to_string(),
+                "toggle 0,0 through 999".to_string(),
+                "turn off 5,5 through 4,4".to_string(),
+            ]),
+            vec![
+                InputProblem::at(1, "expected `turn on`, `turn off` or `toggle`"),
+                InputProblem::at(2, "expected `X,Y through X,Y`"),
+                InputProblem::at(3, "expected the top-left corner first"),
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_instruction_never_panics(s in "\\PC*") {
+            let _ = parse_instruction(&s);
+        }
+
+        #[test]
+        fn test_parse_instruction_never_panics_near_valid(
+            s in "(turn on|turn off|toggle|turn) [0-9]{0,5},?[0-9]{0,5}( through)? ?[0-9]{0,5},?[0-9]{0,5}"
+        ) {
+            prop_assert_eq!(parse_grid_instruction(&s).is_ok(), check_input(std::slice::from_ref(&s)).is_empty());
+        }
+    }
 }
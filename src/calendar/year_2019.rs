@@ -0,0 +1,7 @@
+use crate::calendar::Solver;
+
+/// The Intcode computer, shared by many days of 2019.
+pub mod intcode;
+
+/// Solvers of every day, as `(day, part 1, part 2)`.
+pub const SOLVERS: &[(usize, Solver, Solver)] = &[];
@@ -0,0 +1,707 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use anyhow::{Error, Result};
+use itertools::Itertools;
+
+/// Parse a comma-separated Intcode program.
+pub fn parse_program(text: &str) -> Result<Vec<i64>, Error> {
+    text.trim()
+        .split(',')
+        .enumerate()
+        .map(|(index, value)| {
+            value.trim().parse::<i64>().map_err(|error| {
+                Error::msg(format!(
+                    "Invalid value '{}' at address {}: {}",
+                    value, index, error
+                ))
+            })
+        })
+        .collect()
+}
+
+/// How a parameter is read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// The value at an address.
+    Position,
+    /// The parameter itself.
+    Immediate,
+    /// The value at an address relative to the relative base.
+    Relative,
+}
+
+impl Mode {
+    fn decode(digit: i64) -> Result<Mode, Error> {
+        match digit {
+            0 => Ok(Mode::Position),
+            1 => Ok(Mode::Immediate),
+            2 => Ok(Mode::Relative),
+            _ => Err(Error::msg(format!("Unknown parameter mode {}.", digit))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opcode {
+    Add,
+    Multiply,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustBase,
+    Halt,
+}
+
+impl Opcode {
+    fn decode(code: i64) -> Result<Opcode, Error> {
+        Ok(match code {
+            1 => Opcode::Add,
+            2 => Opcode::Multiply,
+            3 => Opcode::Input,
+            4 => Opcode::Output,
+            5 => Opcode::JumpIfTrue,
+            6 => Opcode::JumpIfFalse,
+            7 => Opcode::LessThan,
+            8 => Opcode::Equals,
+            9 => Opcode::AdjustBase,
+            99 => Opcode::Halt,
+            _ => return Err(Error::msg(format!("Unknown opcode {}.", code))),
+        })
+    }
+
+    /// Number of parameters.
+    pub fn arity(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+
+    /// Index of the parameter written to, if any.
+    fn written(self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => Some(2),
+            Opcode::Input => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "add",
+            Opcode::Multiply => "mul",
+            Opcode::Input => "in",
+            Opcode::Output => "out",
+            Opcode::JumpIfTrue => "jnz",
+            Opcode::JumpIfFalse => "jz",
+            Opcode::LessThan => "lt",
+            Opcode::Equals => "eq",
+            Opcode::AdjustBase => "arb",
+            Opcode::Halt => "halt",
+        }
+    }
+}
+
+/// A decoded instruction: opcode, and mode and raw value of every parameter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub parameters: Vec<(Mode, i64)>,
+}
+
+impl Instruction {
+    /// Decode the instruction at `address`, reading missing memory as 0.
+    pub fn decode(memory: &[i64], address: usize) -> Result<Instruction, Error> {
+        Instruction::decode_with(|address| memory.get(address).copied().unwrap_or(0), address)
+    }
+
+    /// Decode the instruction at `address`, reading memory with `read`.
+    fn decode_with(read: impl Fn(usize) -> i64, address: usize) -> Result<Instruction, Error> {
+        let value: i64 = read(address);
+        if value < 0 {
+            return Err(Error::msg(format!("Negative instruction {}.", value)));
+        }
+        let opcode: Opcode = Opcode::decode(value % 100)?;
+        let mut modes: i64 = value / 100;
+        let mut parameters: Vec<(Mode, i64)> = Vec::with_capacity(opcode.arity());
+        for index in 0..opcode.arity() {
+            let mode: Mode = Mode::decode(modes % 10)?;
+            if mode == Mode::Immediate && opcode.written() == Some(index) {
+                return Err(Error::msg(format!(
+                    "Immediate mode for the written parameter of {}.",
+                    value
+                )));
+            }
+            parameters.push((mode, read(address + 1 + index)));
+            modes /= 10;
+        }
+        if modes != 0 {
+            return Err(Error::msg(format!(
+                "Too many parameter modes in {}.",
+                value
+            )));
+        }
+        Ok(Instruction { opcode, parameters })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.opcode.mnemonic())?;
+        for (index, &(mode, value)) in self.parameters.iter().enumerate() {
+            let separator: &str = if index == 0 { " " } else { ", " };
+            match mode {
+                Mode::Position => write!(f, "{}[{}]", separator, value)?,
+                Mode::Immediate => write!(f, "{}{}", separator, value)?,
+                Mode::Relative => write!(f, "{}[rb{:+}]", separator, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// List the instructions of a program from its first address, one per line. Values
+/// that do not decode are shown as `data`, and the sweep resumes after them.
+pub fn disassemble(memory: &[i64]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut address: usize = 0;
+    while address < memory.len() {
+        match Instruction::decode(memory, address) {
+            Ok(instruction) if address + instruction.opcode.arity() < memory.len() => {
+                lines.push(format!("{:>5}: {}", address, instruction));
+                address += 1 + instruction.opcode.arity();
+            }
+            _ => {
+                lines.push(format!("{:>5}: data {}", address, memory[address]));
+                address += 1;
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Where a computer stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    /// The last instruction ran and the program goes on.
+    Running,
+    /// An input instruction found an empty input queue. It runs again on resume.
+    WaitingForInput,
+    Halted,
+}
+
+/// A copy of the whole state of a computer, to restore later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    memory: Vec<i64>,
+    sparse: HashMap<usize, i64>,
+    ip: usize,
+    relative_base: i64,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+    halted: bool,
+}
+
+/// An Intcode computer, reading from its input queue and writing to its output queue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Computer {
+    memory: Vec<i64>,
+    /// Non-zero values written beyond the program, which may use huge addresses.
+    sparse: HashMap<usize, i64>,
+    ip: usize,
+    relative_base: i64,
+    pub input: VecDeque<i64>,
+    pub output: VecDeque<i64>,
+    halted: bool,
+}
+
+impl Computer {
+    pub fn new(program: &[i64]) -> Computer {
+        Computer {
+            memory: program.to_vec(),
+            sparse: HashMap::new(),
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            halted: false,
+        }
+    }
+
+    /// Read memory, which is 0 where never written beyond the program.
+    pub fn read(&self, address: i64) -> Result<i64, Error> {
+        let address: usize = usize::try_from(address)
+            .map_err(|_| Error::msg(format!("Negative address {}.", address)))?;
+        Ok(self.peek(address))
+    }
+
+    fn peek(&self, address: usize) -> i64 {
+        match self.memory.get(address) {
+            Some(&value) => value,
+            None => self.sparse.get(&address).copied().unwrap_or(0),
+        }
+    }
+
+    /// Write memory, at any non-negative address.
+    pub fn write(&mut self, address: i64, value: i64) -> Result<(), Error> {
+        let address: usize = usize::try_from(address)
+            .map_err(|_| Error::msg(format!("Negative address {}.", address)))?;
+        if let Some(cell) = self.memory.get_mut(address) {
+            *cell = value;
+        } else if value == 0 {
+            self.sparse.remove(&address);
+        } else {
+            self.sparse.insert(address, value);
+        }
+        Ok(())
+    }
+
+    /// The memory holding the program. Values written beyond it are only seen by `read`.
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Address of a position or relative parameter.
+    fn address(&self, (mode, value): (Mode, i64)) -> Result<i64, Error> {
+        match mode {
+            Mode::Position => Ok(value),
+            Mode::Relative => self
+                .relative_base
+                .checked_add(value)
+                .ok_or(Error::msg("Relative address overflow.")),
+            Mode::Immediate => Err(Error::msg("Immediate parameters have no address.")),
+        }
+    }
+
+    fn value(&self, parameter: (Mode, i64)) -> Result<i64, Error> {
+        match parameter.0 {
+            Mode::Immediate => Ok(parameter.1),
+            _ => self.read(self.address(parameter)?),
+        }
+    }
+
+    fn jump(&mut self, target: i64) -> Result<(), Error> {
+        self.ip = usize::try_from(target)
+            .map_err(|_| Error::msg(format!("Jump to negative address {}.", target)))?;
+        Ok(())
+    }
+
+    /// Execute a single instruction.
+    pub fn step(&mut self) -> Result<State, Error> {
+        if self.halted {
+            return Ok(State::Halted);
+        }
+        let instruction: Instruction =
+            Instruction::decode_with(|address| self.peek(address), self.ip)
+                .map_err(|error| Error::msg(format!("At address {}: {}", self.ip, error)))?;
+        let p: &[(Mode, i64)] = &instruction.parameters;
+        let next: usize = self.ip + 1 + p.len();
+        let ip: usize = self.ip;
+        let overflow = move || Error::msg(format!("Arithmetic overflow at address {}.", ip));
+        match instruction.opcode {
+            Opcode::Add => {
+                let sum: i64 = self
+                    .value(p[0])?
+                    .checked_add(self.value(p[1])?)
+                    .ok_or_else(overflow)?;
+                self.write(self.address(p[2])?, sum)?;
+                self.ip = next;
+            }
+            Opcode::Multiply => {
+                let product: i64 = self
+                    .value(p[0])?
+                    .checked_mul(self.value(p[1])?)
+                    .ok_or_else(overflow)?;
+                self.write(self.address(p[2])?, product)?;
+                self.ip = next;
+            }
+            Opcode::Input => {
+                let Some(value) = self.input.pop_front() else {
+                    return Ok(State::WaitingForInput);
+                };
+                self.write(self.address(p[0])?, value)?;
+                self.ip = next;
+            }
+            Opcode::Output => {
+                self.output.push_back(self.value(p[0])?);
+                self.ip = next;
+            }
+            Opcode::JumpIfTrue => match self.value(p[0])? {
+                0 => self.ip = next,
+                _ => self.jump(self.value(p[1])?)?,
+            },
+            Opcode::JumpIfFalse => match self.value(p[0])? {
+                0 => self.jump(self.value(p[1])?)?,
+                _ => self.ip = next,
+            },
+            Opcode::LessThan => {
+                let less: bool = self.value(p[0])? < self.value(p[1])?;
+                self.write(self.address(p[2])?, less as i64)?;
+                self.ip = next;
+            }
+            Opcode::Equals => {
+                let equal: bool = self.value(p[0])? == self.value(p[1])?;
+                self.write(self.address(p[2])?, equal as i64)?;
+                self.ip = next;
+            }
+            Opcode::AdjustBase => {
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(self.value(p[0])?)
+                    .ok_or_else(overflow)?;
+                self.ip = next;
+            }
+            Opcode::Halt => {
+                self.halted = true;
+                return Ok(State::Halted);
+            }
+        }
+        Ok(State::Running)
+    }
+
+    /// Run until the program halts or waits for input.
+    pub fn run(&mut self) -> Result<State, Error> {
+        loop {
+            match self.step()? {
+                State::Running => {}
+                state => return Ok(state),
+            }
+        }
+    }
+
+    /// Feed all the inputs, run to the end and return every output.
+    pub fn run_with_input(&mut self, input: &[i64]) -> Result<Vec<i64>, Error> {
+        self.input.extend(input);
+        match self.run()? {
+            State::Halted => Ok(self.output.drain(..).collect()),
+            _ => Err(Error::msg("The program waits for more input.")),
+        }
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            memory: self.memory.clone(),
+            sparse: self.sparse.clone(),
+            ip: self.ip,
+            relative_base: self.relative_base,
+            input: self.input.clone(),
+            output: self.output.clone(),
+            halted: self.halted,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.memory.clone_from(&snapshot.memory);
+        self.sparse.clone_from(&snapshot.sparse);
+        self.ip = snapshot.ip;
+        self.relative_base = snapshot.relative_base;
+        self.input.clone_from(&snapshot.input);
+        self.output.clone_from(&snapshot.output);
+        self.halted = snapshot.halted;
+    }
+}
+
+/// Run a chain of amplifiers, each started with its phase, feeding 0 to the first one.
+/// With `feedback`, the last output loops back to the first amplifier until they halt.
+/// Returns the last output of the last amplifier.
+pub fn run_amplifiers(program: &[i64], phases: &[i64], feedback: bool) -> Result<i64, Error> {
+    let mut amplifiers: Vec<Computer> = phases
+        .iter()
+        .map(|&phase| {
+            let mut amplifier: Computer = Computer::new(program);
+            amplifier.input.push_back(phase);
+            amplifier
+        })
+        .collect();
+    let mut signal: VecDeque<i64> = VecDeque::from([0]);
+    let mut last: Option<i64> = None;
+    loop {
+        for amplifier in amplifiers.iter_mut() {
+            amplifier.input.extend(signal.drain(..));
+            amplifier.run()?;
+            signal.extend(amplifier.output.drain(..));
+        }
+        last = signal.back().copied().or(last);
+        if !feedback || amplifiers.iter().all(Computer::is_halted) {
+            break;
+        }
+        if signal.is_empty() {
+            return Err(Error::msg("The amplifiers wait without any signal."));
+        }
+    }
+    last.ok_or(Error::msg("The amplifiers produced no signal."))
+}
+
+/// A message between networked computers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub address: i64,
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Computers exchanging packets, written to output as `address, x, y`. Every computer
+/// first receives its own address, and `-1` whenever it waits on an empty queue.
+#[derive(Clone, Debug)]
+pub struct Network {
+    pub computers: Vec<Computer>,
+}
+
+impl Network {
+    pub fn new(program: &[i64], size: usize) -> Network {
+        let computers: Vec<Computer> = (0..size)
+            .map(|address| {
+                let mut computer: Computer = Computer::new(program);
+                computer.input.push_back(address as i64);
+                computer
+            })
+            .collect();
+        Network { computers }
+    }
+
+    /// Queue a packet at its destination, which must be within the network.
+    pub fn send(&mut self, packet: Packet) -> Result<(), Error> {
+        let computer: &mut Computer = usize::try_from(packet.address)
+            .ok()
+            .and_then(|address| self.computers.get_mut(address))
+            .ok_or(Error::msg(format!(
+                "No computer at address {}.",
+                packet.address
+            )))?;
+        computer.input.extend([packet.x, packet.y]);
+        Ok(())
+    }
+
+    /// Run every computer until it waits, and deliver the packets it sent.
+    /// Returns the packets sent outside the network, and whether any packet was sent at all.
+    pub fn round(&mut self) -> Result<(Vec<Packet>, bool), Error> {
+        let mut outside: Vec<Packet> = Vec::new();
+        let mut sent: bool = false;
+        for index in 0..self.computers.len() {
+            let computer: &mut Computer = &mut self.computers[index];
+            if computer.input.is_empty() {
+                computer.input.push_back(-1);
+            }
+            computer.run()?;
+            let output: Vec<i64> = computer.output.drain(..).collect();
+            if !output.len().is_multiple_of(3) {
+                return Err(Error::msg(format!(
+                    "Computer {} sent an incomplete packet.",
+                    index
+                )));
+            }
+            for (address, x, y) in output.into_iter().tuples() {
+                sent = true;
+                let packet: Packet = Packet { address, x, y };
+                if usize::try_from(address).is_ok_and(|a| a < self.computers.len()) {
+                    self.send(packet)?;
+                } else {
+                    outside.push(packet);
+                }
+            }
+        }
+        Ok((outside, sent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn run(program: &str, input: &[i64]) -> Result<Vec<i64>, Error> {
+        Computer::new(&parse_program(program)?).run_with_input(input)
+    }
+
+    #[test]
+    fn test_arithmetic() -> Result<(), Error> {
+        // 2019 day 2.
+        for (program, memory) in [
+            (
+                "1,9,10,3,2,3,11,0,99,30,40,50",
+                "3500,9,10,70,2,3,11,0,99,30,40,50",
+            ),
+            ("1,0,0,0,99", "2,0,0,0,99"),
+            ("2,3,0,3,99", "2,3,0,6,99"),
+            ("2,4,4,5,99,0", "2,4,4,5,99,9801"),
+            ("1,1,1,4,99,5,6,0,99", "30,1,1,4,2,5,6,0,99"),
+            ("1002,4,3,4,33", "1002,4,3,4,99"),
+            ("1101,100,-1,4,0", "1101,100,-1,4,99"),
+        ] {
+            let mut computer: Computer = Computer::new(&parse_program(program)?);
+            assert_eq!(computer.run()?, State::Halted);
+            assert_eq!(computer.memory(), parse_program(memory)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_comparisons_and_jumps() -> Result<(), Error> {
+        // 2019 day 5: equal to 8 and less than 8, in position and immediate modes.
+        for (program, input, expected) in [
+            ("3,9,8,9,10,9,4,9,99,-1,8", 8, 1),
+            ("3,9,8,9,10,9,4,9,99,-1,8", 7, 0),
+            ("3,9,7,9,10,9,4,9,99,-1,8", 7, 1),
+            ("3,3,1108,-1,8,3,4,3,99", 9, 0),
+            ("3,3,1107,-1,8,3,4,3,99", 3, 1),
+            ("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9", 0, 0),
+            ("3,3,1105,-1,9,1101,0,0,12,4,12,99,1", 5, 1),
+        ] {
+            assert_eq!(run(program, &[input])?, vec![expected], "{}", program);
+        }
+        let larger: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,\
+            1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,\
+            999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+        assert_eq!(run(larger, &[7])?, vec![999]);
+        assert_eq!(run(larger, &[8])?, vec![1000]);
+        assert_eq!(run(larger, &[9])?, vec![1001]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_base() -> Result<(), Error> {
+        // 2019 day 9: a quine, and large numbers.
+        let quine: &str = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        assert_eq!(run(quine, &[])?, parse_program(quine)?);
+        assert_eq!(
+            run("1102,34915192,34915192,7,4,7,99,0", &[])?,
+            vec![1219070632396864]
+        );
+        assert_eq!(run("104,1125899906842624,99", &[])?, vec![1125899906842624]);
+        assert_eq!(run("109,-1,203,1,204,1,99", &[42])?, vec![42]);
+        // Memory far beyond the program, without allocating up to it.
+        assert_eq!(
+            run("1101,5,6,1000000000000,4,1000000000000,99", &[])?,
+            vec![11]
+        );
+        assert_eq!(
+            run("1105,1,9223372036854775807", &[])
+                .unwrap_err()
+                .to_string(),
+            "At address 9223372036854775807: Unknown opcode 0."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(run("98", &[]).is_err());
+        assert!(run("11101,1,1,1,99", &[]).is_err());
+        assert!(run("4,-1,99", &[]).is_err());
+        assert!(run("1105,1,-5", &[]).is_err());
+        assert!(run("3,0,99", &[]).is_err());
+        assert!(parse_program("1,2,x").is_err());
+    }
+
+    #[test]
+    fn test_pause_and_snapshot() -> Result<(), Error> {
+        // Output twice the input, forever.
+        let mut computer: Computer = Computer::new(&parse_program("3,9,1002,9,2,9,4,9,1105,1,0")?);
+        assert_eq!(computer.run()?, State::WaitingForInput);
+        let snapshot: Snapshot = computer.snapshot();
+        computer.input.push_back(21);
+        assert_eq!(computer.run()?, State::WaitingForInput);
+        assert_eq!(computer.output.pop_front(), Some(42));
+        computer.restore(&snapshot);
+        computer.input.push_back(5);
+        assert_eq!(computer.run()?, State::WaitingForInput);
+        assert_eq!(computer.output, VecDeque::from([10]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_amplifiers() -> Result<(), Error> {
+        // 2019 day 7.
+        let program: Vec<i64> = parse_program("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0")?;
+        assert_eq!(run_amplifiers(&program, &[4, 3, 2, 1, 0], false)?, 43210);
+        let program: Vec<i64> = parse_program(
+            "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0",
+        )?;
+        assert_eq!(run_amplifiers(&program, &[0, 1, 2, 3, 4], false)?, 54321);
+        let program: Vec<i64> = parse_program(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        )?;
+        assert_eq!(run_amplifiers(&program, &[9, 8, 7, 6, 5], true)?, 139629729);
+        Ok(())
+    }
+
+    #[test]
+    fn test_network() -> Result<(), Error> {
+        // Every node forwards packets to the next address, incrementing `y`.
+        let program: Vec<i64> = parse_program(
+            "3,100,3,101,1007,101,0,103,1005,103,2,3,102,1001,100,1,104,4,104,4,101,1001,102,1,102,4,102,1105,1,2",
+        )?;
+        let mut network: Network = Network::new(&program, 5);
+        network.send(Packet {
+            address: 0,
+            x: 7,
+            y: 0,
+        })?;
+        let mut rounds: usize = 0;
+        loop {
+            let (outside, sent) = network.round()?;
+            rounds += 1;
+            if let Some(&packet) = outside.first() {
+                assert_eq!(
+                    packet,
+                    Packet {
+                        address: 5,
+                        x: 7,
+                        y: 5
+                    }
+                );
+                break;
+            }
+            assert!(sent);
+        }
+        // Packets travel down the addresses within a single round.
+        assert_eq!(rounds, 1);
+        assert_eq!(network.round()?, (vec![], false));
+        assert!(
+            network
+                .send(Packet {
+                    address: 5,
+                    x: 0,
+                    y: 0
+                })
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_disassemble() -> Result<(), Error> {
+        assert_eq!(
+            disassemble(&parse_program("1002,4,3,4,33,109,-1,204,1,99,7")?),
+            "    0: mul [4], 3, [4]\n    4: data 33\n    5: arb -1\n    7: out [rb+1]\n    9: halt\n   10: data 7"
+        );
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_never_panics(program in prop::collection::vec(prop_oneof![-3..300i64, any::<i64>()], 0..40), input in prop::collection::vec(any::<i64>(), 0..4)) {
+            let mut computer: Computer = Computer::new(&program);
+            computer.input.extend(input);
+            let _ = computer.snapshot();
+            for _ in 0..1000 {
+                match computer.step() {
+                    Ok(State::Running) => {}
+                    _ => break,
+                }
+            }
+            let _ = disassemble(&program);
+        }
+    }
+}
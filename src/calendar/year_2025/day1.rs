@@ -1,68 +1,235 @@
 use anyhow::{Error, Result};
+use rand::Rng;
 use std::path::PathBuf;
 
+use crate::check::{InputProblem, check_each_line};
 use crate::io::read_lines;
+use crate::parse_fmt;
+use crate::variants::Variant;
+
+/// Which zero hits count towards the password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CountingMode {
+    /// The dial rests at zero after a rotation.
+    EndOfRotation,
+    /// Any click lands on zero, as with password method 0x434C49434B.
+    EveryClick,
+}
+
+/// A dial numbered `0..size`, pointing at `start` before the first rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DialGeometry {
+    pub size: u64,
+    pub start: u64,
+}
+
+impl DialGeometry {
+    /// The dial of the puzzle.
+    pub const SAFE: DialGeometry = DialGeometry {
+        size: 100,
+        start: 50,
+    };
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.start >= self.size {
+            return Err(Error::msg(format!(
+                "Start {} is not on a dial of size {}.",
+                self.start, self.size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Turn the dial from `position` by `distance` clicks. Returns the new position and
+    /// how many clicks landed on zero.
+    fn rotate(&self, position: u64, direction: char, distance: u32) -> (u64, u64) {
+        let size: u128 = u128::from(self.size);
+        let position: u128 = u128::from(position);
+        // Turning left from `p` mirrors turning right from `-p`.
+        let mirrored: u128 = if direction == 'R' {
+            position
+        } else {
+            (size - position) % size
+        };
+        let target: u128 = mirrored + u128::from(distance);
+        let landing: u128 = if direction == 'R' {
+            target % size
+        } else {
+            (size - target % size) % size
+        };
+        (landing as u64, (target / size) as u64)
+    }
+}
+
+/// Parse a rotation like `L68` into its direction and distance.
+fn parse_rotation(idx: usize, instruction: &str) -> Result<(char, u32), Error> {
+    let (direction, distance): (char, u32) = parse_fmt!("{direction:1}{distance}", instruction)
+        .map_err(|error| Error::msg(format!("Line {}: {}", idx + 1, error)))?;
+    if direction != 'L' && direction != 'R' {
+        return Err(Error::msg(format!(
+            "Unrecognized direction on line {} : {}",
+            idx + 1,
+            instruction
+        )));
+    }
+    Ok((direction, distance))
+}
 
 fn run_instructions(
     instructions: Vec<String>,
-    use_method_0x434c49434b: Option<()>,
-) -> Result<usize, Error> {
-    let mut zero_counter: usize = 0;
-    let mut current_value: i32 = 50;
+    geometry: &DialGeometry,
+    mode: CountingMode,
+) -> Result<u64, Error> {
+    geometry.validate()?;
+    let mut zero_counter: u64 = 0;
+    let mut current_value: u64 = geometry.start;
 
     for (idx, instruction) in instructions.into_iter().enumerate() {
-        let distance: i32 = instruction[1..instruction.len()].parse::<i32>()?;
-        let direction: char = instruction.chars().next().ok_or(Error::msg(format!(
-            "Empty instruction found on line {}",
-            idx
-        )))?;
-
-        match direction {
-            'R' => {
-                for _ in 0..distance {
-                    current_value += 1;
-                    if current_value == 100 {
-                        if use_method_0x434c49434b.is_some() {
-                            zero_counter += 1;
-                        }
-                        current_value = 0;
-                    }
+        let (direction, distance) = parse_rotation(idx, &instruction)?;
+        for _ in 0..distance {
+            if direction == 'R' {
+                current_value += 1;
+                if current_value == geometry.size {
+                    current_value = 0;
                 }
-            }
-            'L' => {
-                for _ in 0..distance {
-                    current_value -= 1;
-                    if current_value == 0 {
-                        if use_method_0x434c49434b.is_some() {
-                            zero_counter += 1;
-                        }
-                    } else if current_value == -1 {
-                        current_value = 99;
-                    }
+            } else {
+                if current_value == 0 {
+                    current_value = geometry.size;
                 }
+                current_value -= 1;
             }
-            _ => {
-                return Err(Error::msg(format!(
-                    "Unrecognized direction on line {} : {}",
-                    idx, instruction
-                )));
+            if mode == CountingMode::EveryClick && current_value == 0 {
+                zero_counter += 1;
             }
         }
-        if use_method_0x434c49434b.is_none() && current_value == 0 {
+        if mode == CountingMode::EndOfRotation && current_value == 0 {
             zero_counter += 1;
         }
     }
     Ok(zero_counter)
 }
 
+/// Index of every instruction during which the dial hit zero, with the number of hits,
+/// computing each rotation in constant time.
+pub fn zero_hits(
+    instructions: &[String],
+    geometry: &DialGeometry,
+    mode: CountingMode,
+) -> Result<Vec<(usize, u64)>, Error> {
+    geometry.validate()?;
+    let mut hits: Vec<(usize, u64)> = Vec::new();
+    let mut current_value: u64 = geometry.start;
+    for (idx, instruction) in instructions.iter().enumerate() {
+        let (direction, distance) = parse_rotation(idx, instruction)?;
+        let (landing, passes) = geometry.rotate(current_value, direction, distance);
+        current_value = landing;
+        let count: u64 = match mode {
+            CountingMode::EndOfRotation => u64::from(current_value == 0),
+            CountingMode::EveryClick => passes,
+        };
+        if count > 0 {
+            hits.push((idx, count));
+        }
+    }
+    Ok(hits)
+}
+
+/// Same as `run_instructions`, counting the zero hits of a rotation by division.
+fn run_instructions_fast(
+    instructions: &[String],
+    geometry: &DialGeometry,
+    mode: CountingMode,
+) -> Result<u64, Error> {
+    Ok(zero_hits(instructions, geometry, mode)?
+        .iter()
+        .map(|&(_, count)| count)
+        .sum())
+}
+
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "naive",
+        part: 1,
+        solve: |lines| {
+            Ok(run_instructions(
+                lines.to_vec(),
+                &DialGeometry::SAFE,
+                CountingMode::EndOfRotation,
+            )?
+            .to_string())
+        },
+    },
+    Variant {
+        name: "fast",
+        part: 1,
+        solve: |lines| {
+            Ok(
+                run_instructions_fast(lines, &DialGeometry::SAFE, CountingMode::EndOfRotation)?
+                    .to_string(),
+            )
+        },
+    },
+    Variant {
+        name: "naive",
+        part: 2,
+        solve: |lines| {
+            Ok(run_instructions(
+                lines.to_vec(),
+                &DialGeometry::SAFE,
+                CountingMode::EveryClick,
+            )?
+            .to_string())
+        },
+    },
+    Variant {
+        name: "fast",
+        part: 2,
+        solve: |lines| {
+            Ok(
+                run_instructions_fast(lines, &DialGeometry::SAFE, CountingMode::EveryClick)?
+                    .to_string(),
+            )
+        },
+    },
+];
+
+/// Check that every line is a rotation, like `L68`.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_each_line(lines, |line| match line.chars().next() {
+        Some('L' | 'R') if line[1..].parse::<u32>().is_ok() => None,
+        _ => Some("expected `L<distance>` or `R<distance>`".to_string()),
+    })
+}
+
+/// Generate `size` rotations of up to 999 clicks.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{}{}",
+                if rng.random_bool(0.5) { 'L' } else { 'R' },
+                rng.random_range(1..1000)
+            )
+        })
+        .collect()
+}
+
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let zero_counter: usize = run_instructions(read_lines(input)?, None)?;
+    let zero_counter: u64 = run_instructions_fast(
+        &read_lines(input)?,
+        &DialGeometry::SAFE,
+        CountingMode::EndOfRotation,
+    )?;
     tracing::info!("Dial pointed at 0 for a total of {} times.", zero_counter);
     Ok(())
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let zero_counter: usize = run_instructions(read_lines(input)?, Some(()))?;
+    let zero_counter: u64 = run_instructions_fast(
+        &read_lines(input)?,
+        &DialGeometry::SAFE,
+        CountingMode::EveryClick,
+    )?;
     tracing::info!("Dial pointed at 0 for a total of {} times.", zero_counter);
     Ok(())
 }
@@ -71,18 +238,20 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
         assert_eq!(
             run_instructions(
-                vec![
+                [
                     "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"
                 ]
                 .iter()
                 .map(|x| x.to_string())
                 .collect::<Vec<String>>(),
-                None
+                &DialGeometry::SAFE,
+                CountingMode::EndOfRotation
             )?,
             3
         );
@@ -93,16 +262,116 @@ mod tests {
     fn test_part_2() -> Result<(), Error> {
         assert_eq!(
             run_instructions(
-                vec![
+                [
                     "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"
                 ]
                 .iter()
                 .map(|x| x.to_string())
                 .collect::<Vec<String>>(),
-                Some(())
+                &DialGeometry::SAFE,
+                CountingMode::EveryClick
             )?,
             6
         );
+        assert_eq!(
+            run_instructions_fast(
+                &["R1000".to_string(), "L50".to_string()],
+                &DialGeometry::SAFE,
+                CountingMode::EveryClick
+            )?,
+            11
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_hits() -> Result<(), Error> {
+        let instructions: Vec<String> = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ]
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+        assert_eq!(
+            zero_hits(
+                &instructions,
+                &DialGeometry::SAFE,
+                CountingMode::EndOfRotation
+            )?,
+            vec![(2, 1), (5, 1), (7, 1)]
+        );
+        assert_eq!(
+            zero_hits(&instructions, &DialGeometry::SAFE, CountingMode::EveryClick)?,
+            vec![(0, 1), (2, 1), (4, 1), (5, 1), (7, 1), (9, 1)]
+        );
+        let small: DialGeometry = DialGeometry { size: 10, start: 0 };
+        assert_eq!(
+            zero_hits(
+                &["L10".to_string(), "R25".to_string(), "L4".to_string()],
+                &small,
+                CountingMode::EveryClick
+            )?,
+            vec![(0, 1), (1, 2)]
+        );
+        for (size, start) in [(10, 10), (0, 0)] {
+            let geometry: DialGeometry = DialGeometry { size, start };
+            assert!(zero_hits(&instructions, &geometry, CountingMode::EveryClick).is_err());
+            assert!(
+                run_instructions(instructions.clone(), &geometry, CountingMode::EveryClick)
+                    .is_err()
+            );
+        }
         Ok(())
     }
+
+    #[test]
+    fn test_check_input() {
+        assert_eq!(
+            check_input(&["L68".to_string(), "R".to_string(), "3-5".to_string()]),
+            vec![
+                InputProblem::at(1, "expected `L<distance>` or `R<distance>`"),
+                InputProblem::at(2, "expected `L<distance>` or `R<distance>`"),
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_rotation_never_panics(s in "\\PC*") {
+            let _ = parse_rotation(0, &s);
+            let _ = run_instructions_fast(&[s], &DialGeometry::SAFE, CountingMode::EveryClick);
+        }
+
+        #[test]
+        fn test_run_instructions_never_panics_near_valid(
+            lines in prop::collection::vec("[LRX]?-?[0-9]{0,4}", 0..8)
+        ) {
+            let is_valid: bool = check_input(&lines).is_empty();
+            prop_assert!(
+                run_instructions(lines.clone(), &DialGeometry::SAFE, CountingMode::EndOfRotation)
+                    .is_ok()
+                    || !is_valid
+            );
+            prop_assert!(
+                run_instructions_fast(&lines, &DialGeometry::SAFE, CountingMode::EveryClick)
+                    .is_ok()
+                    || !is_valid
+            );
+        }
+
+        #[test]
+        fn test_fast_matches_naive(
+            size in 1..50u64,
+            start in 0..50u64,
+            lines in prop::collection::vec("[LR][0-9]{1,3}", 0..12),
+        ) {
+            let geometry: DialGeometry = DialGeometry { size, start: start % size };
+            for mode in [CountingMode::EndOfRotation, CountingMode::EveryClick] {
+                prop_assert_eq!(
+                    run_instructions_fast(&lines, &geometry, mode).unwrap(),
+                    run_instructions(lines.clone(), &geometry, mode).unwrap()
+                );
+            }
+        }
+    }
 }
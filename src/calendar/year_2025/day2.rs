@@ -2,8 +2,12 @@ use std::cmp::min;
 use std::path::PathBuf;
 
 use anyhow::{Error, Result};
+use rand::Rng;
 
-use crate::io::read;
+use crate::check::{InputProblem, check_single_line};
+use crate::io::read_line;
+use crate::parse_fmt;
+use crate::variants::Variant;
 
 fn is_id_invalid(id: &str) -> Result<bool, Error> {
     if id.starts_with('0') {
@@ -50,24 +54,106 @@ enum InvalidVersion {
     V2,
 }
 
-fn sum_invalid_ids(payload: String, version: InvalidVersion) -> Result<usize, Error> {
+fn is_invalid_by_string(id: usize, version: &InvalidVersion) -> Result<bool, Error> {
+    match version {
+        InvalidVersion::V1 => is_id_invalid(&format!("{}", id)),
+        InvalidVersion::V2 => is_id_invalid_v2(&format!("{}", id)),
+    }
+}
+
+/// An ID of `d` digits repeats a block of `p` digits iff it is a multiple of
+/// `(10^d - 1) / (10^p - 1)`, i.e. `1` followed by `0…01` repeated.
+fn is_invalid_by_arithmetic(id: usize, version: &InvalidVersion) -> bool {
+    let num_digits: u32 = count_digits(id);
+    let is_repeated = |block_digits: u32| {
+        num_digits.is_multiple_of(block_digits)
+            && (id as u128)
+                .is_multiple_of((10u128.pow(num_digits) - 1) / (10u128.pow(block_digits) - 1))
+    };
+    match version {
+        InvalidVersion::V1 => num_digits.is_multiple_of(2) && is_repeated(num_digits / 2),
+        InvalidVersion::V2 => (1..=num_digits / 2).any(is_repeated),
+    }
+}
+
+fn count_digits(id: usize) -> u32 {
+    id.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// Sum of the IDs within `first..=last` made of a block of `block_digits` digits
+/// repeated to `num_digits` digits: the multiples `block * (10^d - 1) / (10^p - 1)`.
+fn sum_repeated(first: usize, last: usize, num_digits: u32, block_digits: u32) -> u128 {
+    let multiplier: u128 = (10u128.pow(num_digits) - 1) / (10u128.pow(block_digits) - 1);
+    let low: u128 = (first as u128)
+        .div_ceil(multiplier)
+        .max(10u128.pow(block_digits - 1));
+    let high: u128 = (last as u128 / multiplier).min(10u128.pow(block_digits) - 1);
+    if low > high {
+        return 0;
+    }
+    multiplier * (low + high) * (high - low + 1) / 2
+}
+
+/// Sum of the invalid IDs within `first..=last`, generating them digit count by digit
+/// count instead of testing every ID.
+fn sum_invalid_in_range(first: usize, last: usize, version: &InvalidVersion) -> u128 {
+    (count_digits(first)..=count_digits(last))
+        .map(|num_digits| match version {
+            InvalidVersion::V1 if num_digits.is_multiple_of(2) => {
+                sum_repeated(first, last, num_digits, num_digits / 2)
+            }
+            InvalidVersion::V1 => 0,
+            InvalidVersion::V2 => {
+                // An ID repeating a block of `p` digits also repeats any block whose length
+                // is a multiple of `p`, so it is enough to consider `num_digits / q` for every
+                // prime `q`. IDs with several such periods have their gcd as a period too:
+                // inclusion-exclusion over the sets of primes counts each of them once.
+                let primes: Vec<u32> = (2..=num_digits)
+                    .filter(|&q| num_digits.is_multiple_of(q) && (2..q).all(|r| q % r != 0))
+                    .collect();
+                let mut sum: i128 = 0;
+                for subset in 1..1u32 << primes.len() {
+                    let divisor: u32 = primes
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| subset >> index & 1 == 1)
+                        .map(|(_, q)| q)
+                        .product();
+                    let repeated: i128 =
+                        sum_repeated(first, last, num_digits, num_digits / divisor) as i128;
+                    if subset.count_ones() % 2 == 1 {
+                        sum += repeated;
+                    } else {
+                        sum -= repeated;
+                    }
+                }
+                sum as u128
+            }
+        })
+        .sum()
+}
+
+/// Parse comma-separated ID ranges, like `11-22,95-115`.
+fn parse_ranges(payload: &str) -> Result<Vec<(usize, usize)>, Error> {
+    payload
+        .split(',')
+        .enumerate()
+        .map(|(index, pair)| {
+            parse_fmt!("{first}-{last}", pair)
+                .map_err(|error| Error::msg(format!("Range {}: {}", index + 1, error)))
+        })
+        .collect()
+}
+
+fn sum_invalid_ids(
+    payload: String,
+    version: InvalidVersion,
+    is_invalid: fn(usize, &InvalidVersion) -> Result<bool, Error>,
+) -> Result<usize, Error> {
     let mut invalid_sum: usize = 0;
-    for pair in payload.split(',') {
-        let id_extremes: Vec<&str> = pair.split('-').collect();
-        assert_eq!(id_extremes.len(), 2);
-        for id_num in id_extremes
-            .first()
-            .ok_or(Error::msg("Error retrieving first ID."))?
-            .parse::<usize>()?
-            ..(id_extremes
-                .last()
-                .ok_or(Error::msg("Error retrieving second ID."))?
-                .parse::<usize>()?
-                + 1)
-        {
-            if (version == InvalidVersion::V1 && is_id_invalid(&format!("{}", id_num))?)
-                || (version == InvalidVersion::V2 && is_id_invalid_v2(&format!("{}", id_num))?)
-            {
+    for (first, last) in parse_ranges(&payload)? {
+        for id_num in first..=last {
+            if is_invalid(id_num, &version)? {
                 invalid_sum += id_num;
             }
         }
@@ -75,14 +161,113 @@ fn sum_invalid_ids(payload: String, version: InvalidVersion) -> Result<usize, Er
     Ok(invalid_sum)
 }
 
+fn sum_invalid_ids_enumerated(payload: &str, version: InvalidVersion) -> Result<usize, Error> {
+    let sum: u128 = parse_ranges(payload)?
+        .into_iter()
+        .map(|(first, last)| sum_invalid_in_range(first, last, &version))
+        .sum();
+    usize::try_from(sum).map_err(|_| Error::msg(format!("Invalid IDs sum {} overflows.", sum)))
+}
+
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "naive",
+        part: 1,
+        solve: |lines| {
+            Ok(
+                sum_invalid_ids(lines.join(""), InvalidVersion::V1, is_invalid_by_string)?
+                    .to_string(),
+            )
+        },
+    },
+    Variant {
+        name: "fast",
+        part: 1,
+        solve: |lines| {
+            Ok(
+                sum_invalid_ids(lines.join(""), InvalidVersion::V1, |id, version| {
+                    Ok(is_invalid_by_arithmetic(id, version))
+                })?
+                .to_string(),
+            )
+        },
+    },
+    Variant {
+        name: "enumeration",
+        part: 1,
+        solve: |lines| {
+            Ok(sum_invalid_ids_enumerated(&lines.join(""), InvalidVersion::V1)?.to_string())
+        },
+    },
+    Variant {
+        name: "naive",
+        part: 2,
+        solve: |lines| {
+            Ok(
+                sum_invalid_ids(lines.join(""), InvalidVersion::V2, is_invalid_by_string)?
+                    .to_string(),
+            )
+        },
+    },
+    Variant {
+        name: "fast",
+        part: 2,
+        solve: |lines| {
+            Ok(
+                sum_invalid_ids(lines.join(""), InvalidVersion::V2, |id, version| {
+                    Ok(is_invalid_by_arithmetic(id, version))
+                })?
+                .to_string(),
+            )
+        },
+    },
+    Variant {
+        name: "enumeration",
+        part: 2,
+        solve: |lines| {
+            Ok(sum_invalid_ids_enumerated(&lines.join(""), InvalidVersion::V2)?.to_string())
+        },
+    },
+];
+
+/// Check that the input is a single line of comma-separated ID ranges.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_single_line(lines, |line| {
+        line.split(',').enumerate().find_map(|(index, pair)| {
+            match pair
+                .split_once('-')
+                .map(|(first, last)| (first.parse::<usize>(), last.parse::<usize>()))
+            {
+                Some((Ok(first), Ok(last))) if first <= last => None,
+                Some((Ok(_), Ok(_))) => Some(format!("range {} is decreasing", index + 1)),
+                _ => Some(format!("range {}: expected `N-N`", index + 1)),
+            }
+        })
+    })
+}
+
+/// Generate `size` ID ranges of up to 10 digits, each spanning at most 10000 IDs.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    vec![
+        (0..size.max(1))
+            .map(|_| {
+                let num_digits: u32 = rng.random_range(1..=10);
+                let first: usize = rng.random_range(1..10usize.pow(num_digits));
+                format!("{}-{}", first, first + rng.random_range(0..10000))
+            })
+            .collect::<Vec<String>>()
+            .join(","),
+    ]
+}
+
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let answer: usize = sum_invalid_ids(read(input)?, InvalidVersion::V1)?;
+    let answer: usize = sum_invalid_ids_enumerated(&read_line(input)?, InvalidVersion::V1)?;
     tracing::info!("Invalid IDs sum: {}", answer);
     Ok(())
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let answer: usize = sum_invalid_ids(read(input)?, InvalidVersion::V2)?;
+    let answer: usize = sum_invalid_ids_enumerated(&read_line(input)?, InvalidVersion::V2)?;
     tracing::info!("Invalid IDs sum: {}", answer);
     Ok(())
 }
@@ -91,16 +276,23 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
         assert_eq!(
             sum_invalid_ids(
                 "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"
-                    .to_string(), InvalidVersion::V1
+                    .to_string(), InvalidVersion::V1, is_invalid_by_string
             )?,
             1227775554
         );
+        assert_eq!(
+            sum_invalid_ids_enumerated(EXAMPLE, InvalidVersion::V1)?,
+            1227775554
+        );
         Ok(())
     }
 
@@ -109,10 +301,50 @@ mod tests {
         assert_eq!(
             sum_invalid_ids(
                 "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"
-                    .to_string(), InvalidVersion::V2
+                    .to_string(), InvalidVersion::V2, is_invalid_by_string
             )?,
             4174379265
         );
+        assert_eq!(
+            sum_invalid_ids_enumerated(EXAMPLE, InvalidVersion::V2)?,
+            4174379265
+        );
+        for id in [11, 1010, 123123123, 7] {
+            assert_eq!(
+                is_invalid_by_arithmetic(id, &InvalidVersion::V2),
+                is_invalid_by_string(id, &InvalidVersion::V2)?
+            );
+        }
         Ok(())
     }
+
+    proptest! {
+        #[test]
+        fn test_parse_ranges_never_panics(s in "\\PC*") {
+            let _ = parse_ranges(&s);
+        }
+
+        #[test]
+        fn test_parse_ranges_never_panics_near_valid(s in "([0-9]{0,22}-?[0-9]{0,22},?){0,4}") {
+            let _ = parse_ranges(&s);
+        }
+
+        #[test]
+        fn test_enumeration_matches_naive(first in 0..2_000_000usize, width in 0..5000usize) {
+            for version in [InvalidVersion::V1, InvalidVersion::V2] {
+                let naive: usize = (first..=first + width)
+                    .filter(|&id| is_invalid_by_string(id, &version).unwrap())
+                    .sum();
+                prop_assert_eq!(sum_invalid_in_range(first, first + width, &version), naive as u128);
+            }
+        }
+
+        #[test]
+        fn test_is_invalid_never_panics(id in any::<usize>()) {
+            prop_assert_eq!(
+                Some(is_invalid_by_arithmetic(id, &InvalidVersion::V2)),
+                is_invalid_by_string(id, &InvalidVersion::V2).ok()
+            );
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
 use anyhow::{Error, Result};
+use rand::Rng;
 
+use crate::check::{InputProblem, check_charset, check_each_line};
 use crate::io::read_lines;
+use crate::variants::Variant;
 
 fn get_numeric_string_max_char_and_index(s: String) -> Result<(usize, char), Error> {
     let mut max_char_index: usize = 0;
@@ -20,6 +23,12 @@ fn get_numeric_string_max_char_and_index(s: String) -> Result<(usize, char), Err
 }
 
 fn get_bank_joltage(bank: String, batteries_on: usize) -> Result<String, Error> {
+    if batteries_on == 0 || bank.len() < batteries_on || !bank.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::msg(format!(
+            "Cannot turn on {} batteries of bank '{}'.",
+            batteries_on, bank
+        )));
+    }
     if batteries_on == 1 {
         Ok(get_numeric_string_max_char_and_index(bank)?.1.to_string())
     } else {
@@ -38,26 +47,137 @@ fn get_bank_joltage(bank: String, batteries_on: usize) -> Result<String, Error>
     }
 }
 
+/// The batteries chosen in a bank, by index, and the joltage they produce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Subsequence {
+    pub indices: Vec<usize>,
+    pub value: u128,
+}
+
+/// The largest number made of `k` of the `digits`, kept in order, with a monotonic
+/// stack: a digit evicts the smaller ones before it while enough digits remain.
+pub fn max_subsequence(digits: &[u8], k: usize) -> Result<Subsequence, Error> {
+    if k > digits.len() {
+        return Err(Error::msg(format!(
+            "Cannot choose {} of {} digits.",
+            k,
+            digits.len()
+        )));
+    }
+    if let Some(index) = digits.iter().position(|d| !d.is_ascii_digit()) {
+        return Err(Error::msg(format!(
+            "Invalid digit '{}' at index {}.",
+            char::from(digits[index]),
+            index
+        )));
+    }
+    let mut removable: usize = digits.len() - k;
+    let mut indices: Vec<usize> = Vec::with_capacity(digits.len());
+    for (index, &digit) in digits.iter().enumerate() {
+        while removable > 0 && indices.last().is_some_and(|&last| digits[last] < digit) {
+            indices.pop();
+            removable -= 1;
+        }
+        indices.push(index);
+    }
+    indices.truncate(k);
+    let value: u128 = indices
+        .iter()
+        .try_fold(0u128, |value, &index| {
+            value
+                .checked_mul(10)?
+                .checked_add(u128::from(digits[index] - b'0'))
+        })
+        .ok_or(Error::msg(format!("A number of {} digits overflows.", k)))?;
+    Ok(Subsequence { indices, value })
+}
+
 fn get_total_output_joltage(
     battery_banks: Vec<String>,
     batteries_on_per_bank: usize,
-) -> Result<usize, Error> {
-    let mut total_output_joltage: usize = 0;
+) -> Result<u128, Error> {
+    let mut total_output_joltage: u128 = 0;
+    for bank in battery_banks {
+        let bank_joltage: u128 = max_subsequence(bank.as_bytes(), batteries_on_per_bank)
+            .map_err(|error| Error::msg(format!("Bank '{}': {}", bank, error)))?
+            .value;
+        total_output_joltage = total_output_joltage
+            .checked_add(bank_joltage)
+            .ok_or(Error::msg("Total output joltage is too large."))?;
+    }
+    Ok(total_output_joltage)
+}
+
+fn get_total_output_joltage_naive(
+    battery_banks: Vec<String>,
+    batteries_on_per_bank: usize,
+) -> Result<u128, Error> {
+    let mut total_output_joltage: u128 = 0;
     for bank in battery_banks {
         let bank_joltage: String = get_bank_joltage(bank, batteries_on_per_bank)?;
-        total_output_joltage += bank_joltage.parse::<usize>()?;
+        total_output_joltage = total_output_joltage
+            .checked_add(bank_joltage.parse::<u128>()?)
+            .ok_or(Error::msg("Total output joltage is too large."))?;
     }
     Ok(total_output_joltage)
 }
 
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "naive",
+        part: 1,
+        solve: |lines| Ok(get_total_output_joltage_naive(lines.to_vec(), 2)?.to_string()),
+    },
+    Variant {
+        name: "stack",
+        part: 1,
+        solve: |lines| Ok(get_total_output_joltage(lines.to_vec(), 2)?.to_string()),
+    },
+    Variant {
+        name: "naive",
+        part: 2,
+        solve: |lines| Ok(get_total_output_joltage_naive(lines.to_vec(), 12)?.to_string()),
+    },
+    Variant {
+        name: "stack",
+        part: 2,
+        solve: |lines| Ok(get_total_output_joltage(lines.to_vec(), 12)?.to_string()),
+    },
+];
+
+/// Check that every bank holds enough batteries for both parts.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_each_line(lines, |line| {
+        if line.len() < 12 {
+            Some(format!(
+                "expected at least 12 batteries, found {}",
+                line.len()
+            ))
+        } else {
+            check_charset(line, "0123456789")
+        }
+    })
+}
+
+/// Generate `size` banks of 100 batteries.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            (0..100)
+                .map(|_| char::from(rng.random_range(b'1'..=b'9')))
+                .collect()
+        })
+        .collect()
+}
+
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let total_output_joltage: usize = get_total_output_joltage(read_lines(input)?, 2)?;
+    let total_output_joltage: u128 = get_total_output_joltage(read_lines(input)?, 2)?;
     tracing::info!("The total output joltage is {}.", total_output_joltage);
     Ok(())
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let total_output_joltage: usize = get_total_output_joltage(read_lines(input)?, 12)?;
+    let total_output_joltage: u128 = get_total_output_joltage(read_lines(input)?, 12)?;
     tracing::info!("The total output joltage is {}.", total_output_joltage);
     Ok(())
 }
@@ -66,6 +186,7 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
@@ -100,4 +221,53 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_max_subsequence() -> Result<(), Error> {
+        assert_eq!(
+            max_subsequence(b"818181911112111", 2)?,
+            Subsequence {
+                indices: vec![6, 11],
+                value: 92
+            }
+        );
+        assert_eq!(max_subsequence(b"12345", 5)?.value, 12345);
+        assert_eq!(
+            max_subsequence(b"12345", 0)?,
+            Subsequence {
+                indices: vec![],
+                value: 0
+            }
+        );
+        assert!(max_subsequence(b"12345", 6).is_err());
+        assert!(max_subsequence(b"12a45", 2).is_err());
+        assert_eq!(max_subsequence(&[b'9'; 38], 38)?.value, 10u128.pow(38) - 1);
+        assert!(max_subsequence(&[b'9'; 39], 39).is_err());
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_get_bank_joltage_never_panics(bank in "\\PC*", batteries_on in 0..16usize) {
+            let _ = get_bank_joltage(bank, batteries_on);
+        }
+
+        #[test]
+        fn test_get_bank_joltage_never_panics_near_valid(
+            bank in "[0-9]{0,16}[a ]?[0-9]{0,4}",
+            batteries_on in 0..16usize
+        ) {
+            let is_valid: bool = bank.len() >= batteries_on && batteries_on > 0
+                && bank.chars().all(|c| c.is_ascii_digit());
+            prop_assert_eq!(get_bank_joltage(bank, batteries_on).is_ok(), is_valid);
+        }
+
+        #[test]
+        fn test_max_subsequence_matches_recursion(bank in "[0-9]{1,30}", k in 1..30usize) {
+            prop_assume!(k <= bank.len());
+            let subsequence: Subsequence = max_subsequence(bank.as_bytes(), k).unwrap();
+            prop_assert!(subsequence.indices.windows(2).all(|pair| pair[0] < pair[1]));
+            prop_assert_eq!(subsequence.value, get_bank_joltage(bank, k).unwrap().parse::<u128>().unwrap());
+        }
+    }
 }
@@ -1,77 +1,206 @@
-use std::{cmp::min, path::PathBuf};
+use std::path::PathBuf;
 
 use anyhow::{Error, Result};
-use ndarray::{Array2, s};
+use itertools::Either;
+use rand::Rng;
 
+use crate::check::{InputProblem, check_grid};
+use crate::grid::{Grid, Position};
 use crate::io::read_lines;
+use crate::variants::Variant;
 
-fn count_roll_neighbors(map: &Array2<char>) -> Result<Array2<usize>, Error> {
-    let mut neighborhood_sizes: Array2<usize> = Array2::zeros((map.nrows(), map.ncols()));
-    for row_index in 0..map.nrows() {
-        for col_index in 0..map.ncols() {
-            if *map
-                .get((row_index, col_index))
-                .ok_or(Error::msg("Failed to retrive location content."))?
-                != '@'
-            {
-                continue;
-            }
-            let row_start: usize = if row_index == 0 { 0 } else { row_index - 1 };
-            let row_end: usize = min(map.nrows(), row_index + 2);
-            let col_start: usize = if col_index == 0 { 0 } else { col_index - 1 };
-            let col_end: usize = min(map.ncols(), col_index + 2);
-            *neighborhood_sizes.slice_mut(s![row_start..row_end, col_start..col_end]) += 1;
-            *neighborhood_sizes
-                .get_mut((row_index, col_index))
-                .ok_or(Error::msg("Failed to retrieve roll location."))? -= 1;
-        }
-    }
-    Ok(neighborhood_sizes)
+fn build_roll_map(lines: &[String]) -> Result<Grid<char>, Error> {
+    Grid::parse_with(lines, |c| match c {
+        '.' | '@' => Ok(c),
+        _ => Err(Error::msg(format!("Unrecognized character '{}'.", c))),
+    })
 }
 
-fn get_accessibility_mask(map: &Array2<char>) -> Result<Array2<bool>, Error> {
-    let neighborhood_size: Array2<usize> = count_roll_neighbors(map)?;
-    Ok(neighborhood_size.mapv(|v| v < 4) & map.mapv(|c| c == '@'))
+fn count_roll_neighbors(map: &Grid<char>) -> Result<Grid<usize>, Error> {
+    Ok(map.map_indexed(|pos, _| {
+        map.neighbours8(pos)
+            .filter(|&neighbour| map[neighbour] == '@')
+            .count()
+    }))
 }
 
-fn count_accessible_rolls(accessibility_map: &Array2<bool>) -> Result<usize, Error> {
+fn get_accessibility_mask(map: &Grid<char>) -> Result<Grid<bool>, Error> {
+    let neighborhood_size: Grid<usize> = count_roll_neighbors(map)?;
+    Ok(map.map_indexed(|pos, &c| c == '@' && neighborhood_size[pos] < 4))
+}
+
+fn count_accessible_rolls(accessibility_map: &Grid<bool>) -> Result<usize, Error> {
     Ok(accessibility_map.iter().filter(|&&v| v).count())
 }
 
-fn count_removable_rolls(mut map: Array2<char>) -> Result<usize, Error> {
-    let accessibility_map: Array2<bool> = get_accessibility_mask(&map)?;
+fn count_removable_rolls(mut map: Grid<char>) -> Result<usize, Error> {
+    let accessibility_map: Grid<bool> = get_accessibility_mask(&map)?;
     let num_accessible_rolls: usize = count_accessible_rolls(&accessibility_map)?;
     if num_accessible_rolls == 0 {
         return Ok(0);
     }
-    for ((row_index, col_index), &is_accessible) in accessibility_map.indexed_iter() {
-        if is_accessible {
-            map[(row_index, col_index)] = '.';
-        }
+    for pos in accessibility_map.find_all(|&is_accessible| is_accessible) {
+        map[pos] = '.';
     }
     Ok(num_accessible_rolls + count_removable_rolls(map)?)
 }
 
-fn build_roll_map(map_string: Vec<String>) -> Result<Array2<char>, Error> {
-    Ok(Array2::from_shape_vec(
-        (map_string.len(), map_string[0].len()),
-        map_string
-            .iter()
-            .flat_map(|s| s.chars().collect::<Vec<char>>())
-            .collect(),
-    )?)
+/// Which cells around a roll are its neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Orthogonal neighbours only, which the puzzle itself never uses.
+    Four,
+    Eight,
+}
+
+impl Neighbourhood {
+    fn neighbours<T>(self, map: &Grid<T>, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        match self {
+            Neighbourhood::Four => Either::Left(map.neighbours4(pos)),
+            Neighbourhood::Eight => Either::Right(map.neighbours8(pos)),
+        }
+    }
+}
+
+/// A roll can be removed when fewer than `threshold` of its neighbours are rolls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeelingRules {
+    pub threshold: usize,
+    pub neighbourhood: Neighbourhood,
+}
+
+impl PeelingRules {
+    /// The forklifts of the puzzle.
+    pub const FORKLIFT: PeelingRules = PeelingRules {
+        threshold: 4,
+        neighbourhood: Neighbourhood::Eight,
+    };
+}
+
+/// The round, from 1, in which every roll is removed when removing all the accessible
+/// rolls at once, round after round. `None` for the rolls that are never removed.
+///
+/// Neighbour counts are kept up to date as rolls go, so that each round only looks at
+/// the neighbours of the rolls just removed.
+pub fn removal_rounds(map: &Grid<char>, rules: &PeelingRules) -> Grid<Option<usize>> {
+    let mut counts: Grid<usize> = map.map_indexed(|pos, _| {
+        rules
+            .neighbourhood
+            .neighbours(map, pos)
+            .filter(|&neighbour| map[neighbour] == '@')
+            .count()
+    });
+    let mut rounds: Grid<Option<usize>> = map.map_indexed(|_, _| None);
+    let mut removed: Vec<Position> = map
+        .find_all(|&c| c == '@')
+        .into_iter()
+        .filter(|&pos| counts[pos] < rules.threshold)
+        .collect();
+    let mut round: usize = 1;
+    while !removed.is_empty() {
+        for &pos in &removed {
+            rounds[pos] = Some(round);
+        }
+        let mut next: Vec<Position> = Vec::new();
+        for &pos in &removed {
+            for neighbour in rules.neighbourhood.neighbours(map, pos) {
+                if map[neighbour] != '@' || rounds[neighbour].is_some() {
+                    continue;
+                }
+                counts[neighbour] -= 1;
+                // Only the removal making it accessible queues a roll.
+                if counts[neighbour] + 1 == rules.threshold {
+                    next.push(neighbour);
+                }
+            }
+        }
+        removed = next;
+        round += 1;
+    }
+    rounds
+}
+
+/// Number of rolls removed in each round.
+pub fn removals_per_round(rounds: &Grid<Option<usize>>) -> Vec<usize> {
+    let mut removals: Vec<usize> = Vec::new();
+    for &round in rounds.iter().flatten() {
+        if removals.len() < round {
+            removals.resize(round, 0);
+        }
+        removals[round - 1] += 1;
+    }
+    removals
+}
+
+fn count_removed_rolls(lines: &[String], max_round: usize) -> Result<usize, Error> {
+    let rounds: Grid<Option<usize>> =
+        removal_rounds(&build_roll_map(lines)?, &PeelingRules::FORKLIFT);
+    Ok(rounds
+        .iter()
+        .filter(|round| round.is_some_and(|round| round <= max_round))
+        .count())
+}
+
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "naive",
+        part: 1,
+        solve: |lines| {
+            Ok(
+                count_accessible_rolls(&get_accessibility_mask(&build_roll_map(lines)?)?)?
+                    .to_string(),
+            )
+        },
+    },
+    Variant {
+        name: "worklist",
+        part: 1,
+        solve: |lines| Ok(count_removed_rolls(lines, 1)?.to_string()),
+    },
+    Variant {
+        name: "naive",
+        part: 2,
+        solve: |lines| Ok(count_removable_rolls(build_roll_map(lines)?)?.to_string()),
+    },
+    Variant {
+        name: "worklist",
+        part: 2,
+        solve: |lines| Ok(count_removed_rolls(lines, usize::MAX)?.to_string()),
+    },
+];
+
+/// Check that the input is a rectangular map of rolls.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    check_grid(lines, ".@")
+}
+
+/// Generate a `size`x`size` map, with rolls on about 60% of the locations.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.random_bool(0.6) { '@' } else { '.' })
+                .collect()
+        })
+        .collect()
 }
 
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
     let num_accessible_rolls: usize = count_accessible_rolls(&get_accessibility_mask(
-        &build_roll_map(read_lines(input)?)?,
+        &build_roll_map(&read_lines(input)?)?,
     )?)?;
     tracing::info!("Found {} accessible rolls.", num_accessible_rolls);
     Ok(())
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let num_removable_rolls: usize = count_removable_rolls(build_roll_map(read_lines(input)?)?)?;
+    let rounds: Grid<Option<usize>> = removal_rounds(
+        &build_roll_map(&read_lines(input)?)?,
+        &PeelingRules::FORKLIFT,
+    );
+    let removals: Vec<usize> = removals_per_round(&rounds);
+    tracing::debug!("Rolls removed per round: {:?}", removals);
+    let num_removable_rolls: usize = removals.iter().sum();
     tracing::info!("Found {} removable rolls.", num_removable_rolls);
     Ok(())
 }
@@ -80,11 +209,30 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn example() -> Vec<String> {
+        [
+            "..@@.@@@@.",
+            "@@@.@.@.@@",
+            "@@@@@.@.@@",
+            "@.@@@@..@.",
+            "@@.@@@@.@@",
+            ".@@@@@@@.@",
+            ".@.@.@.@@@",
+            "@.@@@.@@@@",
+            ".@@@@@@@@.",
+            "@.@.@@@.@.",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
         assert_eq!(
-            count_accessible_rolls(&get_accessibility_mask(&build_roll_map(vec![
+            count_accessible_rolls(&get_accessibility_mask(&build_roll_map(&[
                 "..@@.@@@@.".to_string(),
                 "@@@.@.@.@@".to_string(),
                 "@@@@@.@.@@".to_string(),
@@ -104,7 +252,7 @@ mod tests {
     #[test]
     fn test_part_2() -> Result<(), Error> {
         assert_eq!(
-            count_removable_rolls(build_roll_map(vec![
+            count_removable_rolls(build_roll_map(&[
                 "..@@.@@@@.".to_string(),
                 "@@@.@.@.@@".to_string(),
                 "@@@@@.@.@@".to_string(),
@@ -120,4 +268,58 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_removal_rounds() -> Result<(), Error> {
+        let map: Grid<char> = build_roll_map(&example())?;
+        let rounds: Grid<Option<usize>> = removal_rounds(&map, &PeelingRules::FORKLIFT);
+        assert_eq!(
+            removals_per_round(&rounds),
+            vec![13, 12, 7, 5, 2, 1, 1, 1, 1]
+        );
+        assert_eq!(count_removed_rolls(&example(), 1)?, 13);
+        assert_eq!(count_removed_rolls(&example(), usize::MAX)?, 43);
+
+        // A plus sign: only the arms go with 4 neighbours, then the centre is alone.
+        let plus: Grid<char> =
+            build_roll_map(&[".@.".to_string(), "@@@".to_string(), ".@.".to_string()])?;
+        let rules: PeelingRules = PeelingRules {
+            threshold: 2,
+            neighbourhood: Neighbourhood::Four,
+        };
+        assert_eq!(
+            removal_rounds(&plus, &rules),
+            Grid::parse_with(
+                &["-1-".to_string(), "121".to_string(), "-1-".to_string()],
+                |c| { Ok(c.to_digit(10).map(|round| round as usize)) }
+            )?
+        );
+        let never: PeelingRules = PeelingRules {
+            threshold: 0,
+            neighbourhood: Neighbourhood::Eight,
+        };
+        assert_eq!(removals_per_round(&removal_rounds(&map, &never)), vec![]);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_build_roll_map_never_panics(map in prop::collection::vec("\\PC{0,8}", 0..8)) {
+            let _ = build_roll_map(&map);
+        }
+
+        #[test]
+        fn test_build_roll_map_never_panics_near_valid(
+            map in prop::collection::vec("[.@x]{3,4}", 1..6)
+        ) {
+            let is_valid: bool = check_input(&map).is_empty();
+            prop_assert_eq!(build_roll_map(&map).is_ok(), is_valid);
+        }
+
+        #[test]
+        fn test_worklist_matches_naive(lines in prop::collection::vec("[.@]{12}", 1..12)) {
+            prop_assert_eq!(count_removed_rolls(&lines, 1).unwrap(), count_accessible_rolls(&get_accessibility_mask(&build_roll_map(&lines).unwrap()).unwrap()).unwrap());
+            prop_assert_eq!(count_removed_rolls(&lines, usize::MAX).unwrap(), count_removable_rolls(build_roll_map(&lines).unwrap()).unwrap());
+        }
+    }
 }
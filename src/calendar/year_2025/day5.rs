@@ -1,109 +1,95 @@
 use std::path::PathBuf;
 
 use anyhow::{Error, Result};
-use pretty_assertions::assert_eq;
+use rand::Rng;
 
+use crate::check::InputProblem;
 use crate::io::read_lines;
+use crate::parse_fmt;
+use crate::range_set::RangeSet;
 
-fn parse_range(range_str: String) -> Result<(usize, usize), Error> {
-    let parsed_range: Vec<usize> = range_str
-        .split("-")
-        .map(|s| -> Result<usize, Error> { Ok(s.parse::<usize>()?) })
-        .collect::<Result<Vec<usize>>>()?;
-    assert_eq!(parsed_range.len(), 2);
-    Ok((parsed_range[0], parsed_range[1]))
+fn parse_range(range_str: &str) -> Result<(usize, usize), Error> {
+    let (first, last): (usize, usize) = parse_fmt!("{first}-{last}", range_str)?;
+    if first > last {
+        return Err(Error::msg(format!("Decreasing range: {}", range_str)));
+    }
+    Ok((first, last))
 }
 
-fn is_ingredient_fresh(
-    ingredient_id: usize,
-    fresh_ranges: &Vec<(usize, usize)>,
-) -> Result<bool, Error> {
-    for range in fresh_ranges {
-        if (ingredient_id <= range.1) & (ingredient_id >= range.0) {
-            return Ok(true);
-        }
+/// Parse the fresh ranges, up to the first blank line.
+fn parse_fresh_ranges(database: &[String]) -> Result<RangeSet<usize>, Error> {
+    let mut fresh_ranges: RangeSet<usize> = RangeSet::new();
+    for (index, line) in database
+        .iter()
+        .take_while(|line| !line.is_empty())
+        .enumerate()
+    {
+        let (first, last) = parse_range(line)
+            .map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))?;
+        fresh_ranges.insert(first..=last);
     }
-    Ok(false)
+    Ok(fresh_ranges)
 }
 
 fn count_fresh_ingredients(database: Vec<String>) -> Result<usize, Error> {
+    let fresh_ranges: RangeSet<usize> = parse_fresh_ranges(&database)?;
     let mut num_fresh_ingredients: usize = 0;
-
-    let mut ranges: Vec<(usize, usize)> = Vec::new();
-    let mut parsed_all_ranges: bool = false;
-    for line in database {
-        if line.is_empty() {
-            parsed_all_ranges = true;
-            continue;
-        }
-        match parsed_all_ranges {
-            false => {
-                ranges.push(parse_range(line)?);
-            }
-            true => {
-                if is_ingredient_fresh(line.parse::<usize>()?, &ranges)? {
-                    num_fresh_ingredients += 1;
-                }
-            }
+    for line in database
+        .iter()
+        .skip_while(|line| !line.is_empty())
+        .filter(|line| !line.is_empty())
+    {
+        if fresh_ranges.contains(line.parse::<usize>()?) {
+            num_fresh_ingredients += 1;
         }
     }
-
     Ok(num_fresh_ingredients)
 }
 
-fn merge_overlapping_ranges(ranges: Vec<(usize, usize)>) -> Result<Vec<(usize, usize)>, Error> {
-    if ranges.len() == 1 {
-        return Ok(ranges.clone());
-    }
-    let mut previous_merge_output: Vec<(usize, usize)> = ranges.clone();
-
-    loop {
-        let mut candidate_range: (usize, usize) = previous_merge_output[0];
-        let mut unmerged_ranges: Vec<(usize, usize)> = Vec::new();
-        for range in previous_merge_output.iter().skip(1) {
-            if (candidate_range.0 >= range.0) & (candidate_range.1 <= range.1) {
-                // Upper range
-                candidate_range = *range;
-            } else if (range.0 >= candidate_range.0) & (range.1 <= candidate_range.1) {
-                // Includes already.
-            } else if (candidate_range.0 >= range.0) & (candidate_range.0 <= range.1) {
-                // Overlap left
-                candidate_range = (range.0, candidate_range.1);
-            } else if (candidate_range.1 >= range.0) & (candidate_range.1 <= range.1) {
-                // Overlap right
-                candidate_range = (candidate_range.0, range.1);
-            } else {
-                // No overlap
-                unmerged_ranges.push(*range);
-            }
-        }
-        let mut merged_ranges: Vec<(usize, usize)> = vec![candidate_range];
-        if !unmerged_ranges.is_empty() {
-            merged_ranges.append(&mut merge_overlapping_ranges(unmerged_ranges)?);
-        }
-
-        if merged_ranges.len() == previous_merge_output.len() {
-            break;
-        }
-        previous_merge_output = merged_ranges;
-    }
-    //dbg!(&previous_merge_output);
-    Ok(previous_merge_output)
+fn count_potential_fresh_ingredients(database: Vec<String>) -> Result<usize, Error> {
+    Ok(usize::try_from(parse_fresh_ranges(&database)?.len())?)
 }
 
-fn count_potential_fresh_ingredients(database: Vec<String>) -> Result<usize, Error> {
-    let mut ranges: Vec<(usize, usize)> = Vec::new();
-    for line in database {
-        if line.is_empty() {
-            break;
+/// Check that the database holds ranges, a blank line, then ingredient IDs.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    let mut problems: Vec<InputProblem> = Vec::new();
+    let Some(separator) = lines.iter().position(|l| l.is_empty()) else {
+        return vec![InputProblem::global(
+            "expected a blank line between ranges and IDs",
+        )];
+    };
+    for (index, line) in lines.iter().enumerate() {
+        if index < separator {
+            match line
+                .split_once('-')
+                .map(|(first, last)| (first.parse::<usize>(), last.parse::<usize>()))
+            {
+                Some((Ok(first), Ok(last))) if first <= last => {}
+                Some((Ok(_), Ok(_))) => problems.push(InputProblem::at(index, "decreasing range")),
+                _ => problems.push(InputProblem::at(index, "expected `N-N`")),
+            }
+        } else if index > separator && line.parse::<usize>().is_err() {
+            problems.push(InputProblem::at(index, "expected an ingredient ID"));
         }
-        ranges.push(parse_range(line)?);
     }
-    let mut num_potential_fresh_ingredients: usize = 0;
-    for range in merge_overlapping_ranges(ranges)? {
-        num_potential_fresh_ingredients += (range.1 - range.0) + 1;
+    if separator == 0 {
+        problems.push(InputProblem::global("no fresh ranges"));
     }
-    Ok(num_potential_fresh_ingredients)
+    problems
+}
+
+/// Generate `size` overlapping fresh ranges and `size` ingredient IDs.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    let max_id: usize = 1_000_000_000_000;
+    let mut lines: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let first: usize = rng.random_range(1..max_id);
+            format!("{}-{}", first, first + rng.random_range(0..max_id / 100))
+        })
+        .collect();
+    lines.push("".to_string());
+    lines.extend((0..size).map(|_| rng.random_range(1..max_id + max_id / 100).to_string()));
+    lines
 }
 
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
@@ -126,6 +112,7 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
@@ -168,4 +155,42 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_check_input() {
+        assert_eq!(
+            check_input(&[
+                "3-5".to_string(),
+                "5-3".to_string(),
+                "".to_string(),
+                "1".to_string(),
+                "1-2".to_string(),
+            ]),
+            vec![
+                InputProblem::at(1, "decreasing range"),
+                InputProblem::at(4, "expected an ingredient ID"),
+            ]
+        );
+        assert_eq!(
+            check_input(&["3-5".to_string()]),
+            vec![InputProblem::global(
+                "expected a blank line between ranges and IDs"
+            )]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_range_never_panics(s in "\\PC*") {
+            let _ = parse_range(&s);
+        }
+
+        #[test]
+        fn test_parsers_never_panic_near_valid(
+            database in prop::collection::vec("[0-9]{0,21}-?-?[0-9]{0,21}", 0..6)
+        ) {
+            let _ = count_fresh_ingredients(database.clone());
+            let _ = count_potential_fresh_ingredients(database);
+        }
+    }
 }
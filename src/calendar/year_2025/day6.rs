@@ -1,104 +1,268 @@
+use std::fmt;
 use std::path::PathBuf;
 
 use anyhow::{Error, Result};
-use ndarray::Array1;
+use itertools::Itertools;
+use rand::Rng;
 
+use crate::check::InputProblem;
 use crate::io::read_lines;
 
-enum OperationType {
-    Sum,
-    Prod,
-    Unknown,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
 }
 
-struct Problem {
-    pub factors: Vec<usize>,
-    pub operation_type: OperationType,
-}
+impl Operation {
+    fn parse(c: char) -> Option<Operation> {
+        match c {
+            '+' => Some(Operation::Add),
+            '-' => Some(Operation::Subtract),
+            '*' => Some(Operation::Multiply),
+            '/' => Some(Operation::Divide),
+            _ => None,
+        }
+    }
 
-impl Problem {
-    pub fn solve(&self) -> Result<usize, Error> {
-        match self.operation_type {
-            OperationType::Sum => Ok(Array1::from_vec(self.factors.clone()).sum()),
-            OperationType::Prod => Ok(Array1::from_vec(self.factors.clone()).product()),
-            OperationType::Unknown => Err(Error::msg("Unknown operation for problem solving.")),
+    fn symbol(self) -> char {
+        match self {
+            Operation::Add => '+',
+            Operation::Subtract => '-',
+            Operation::Multiply => '*',
+            Operation::Divide => '/',
         }
     }
 }
 
-fn calculate_grand_total(homework: Vec<String>) -> Result<usize, Error> {
-    let mut grand_total: usize = 0;
-    let mut problems: Vec<Problem> = Vec::new();
-    for line in homework {
-        let factors: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
-        if problems.is_empty() {
-            for f in factors {
-                problems.push(Problem {
-                    factors: vec![f.parse::<usize>()?],
-                    operation_type: OperationType::Unknown,
-                });
-            }
-        } else if !["+", "*"].contains(&factors[0]) {
-            for (problem_index, f) in factors.iter().enumerate() {
-                problems[problem_index].factors.push(f.parse::<usize>()?);
-            }
-        } else {
-            for (problem_index, f) in factors.iter().enumerate() {
-                problems[problem_index].operation_type = match *f {
-                    "+" => Ok(OperationType::Sum),
-                    "*" => Ok(OperationType::Prod),
-                    _ => Err(Error::msg("Unknown operation.")),
-                }?;
+/// A problem of the worksheet, applying its operation from left to right.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expression {
+    pub operation: Operation,
+    pub operands: Vec<u64>,
+}
+
+impl Expression {
+    /// The value of the expression, with divisions rounding towards zero.
+    pub fn evaluate(&self) -> Result<i128, Error> {
+        let (&first, rest) = self.operands.split_first().ok_or(Error::msg(format!(
+            "No operands for '{}'.",
+            self.operation.symbol()
+        )))?;
+        rest.iter().try_fold(i128::from(first), |acc, &operand| {
+            let operand: i128 = i128::from(operand);
+            match self.operation {
+                Operation::Add => acc.checked_add(operand),
+                Operation::Subtract => acc.checked_sub(operand),
+                Operation::Multiply => acc.checked_mul(operand),
+                Operation::Divide => acc.checked_div(operand),
             }
+            .ok_or(Error::msg(format!("Cannot evaluate {}.", self)))
+        })
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator: String = format!(" {} ", self.operation.symbol());
+        write!(f, "{}", self.operands.iter().join(&separator))
+    }
+}
+
+/// The columns of a single problem: the digits and spaces of every number row,
+/// padded to the same width, and the operation below them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Block {
+    rows: Vec<Vec<char>>,
+    operation: Operation,
+}
+
+/// Split a worksheet into its problems, at the columns that are blank on every line.
+/// Lines may be ragged: missing characters count as spaces. The last non-blank line
+/// holds the operations.
+fn parse_worksheet(homework: &[String]) -> Result<Vec<Block>, Error> {
+    let lines: Vec<Vec<char>> = homework.iter().map(|line| line.chars().collect()).collect();
+    let num_lines: usize = lines
+        .iter()
+        .rposition(|line| line.iter().any(|&c| c != ' '))
+        .ok_or(Error::msg("Empty homework."))?
+        + 1;
+    let (operations, numbers) = lines[..num_lines]
+        .split_last()
+        .ok_or(Error::msg("Empty homework."))?;
+    if numbers.is_empty() {
+        return Err(Error::msg("No numbers above the operations."));
+    }
+    let cell = |line: &[char], col: usize| line.get(col).copied().unwrap_or(' ');
+    for (index, line) in numbers.iter().enumerate() {
+        if let Some(&c) = line.iter().find(|&&c| c != ' ' && !c.is_ascii_digit()) {
+            return Err(Error::msg(format!(
+                "Invalid character '{}' on line {}.",
+                c,
+                index + 1
+            )));
         }
     }
-    for p in problems {
-        grand_total += p.solve()?;
+
+    let width: usize = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let is_blank = |col: usize| lines[..num_lines].iter().all(|line| cell(line, col) == ' ');
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut col: usize = 0;
+    while col < width {
+        if is_blank(col) {
+            col += 1;
+            continue;
+        }
+        let start: usize = col;
+        while col < width && !is_blank(col) {
+            col += 1;
+        }
+        let symbols: Vec<char> = (start..col)
+            .map(|c| cell(operations, c))
+            .filter(|&c| c != ' ')
+            .collect();
+        let operation: Operation = match symbols[..] {
+            [symbol] => Operation::parse(symbol).ok_or(Error::msg(format!(
+                "Unknown operation '{}' in column {}.",
+                symbol,
+                start + 1
+            )))?,
+            _ => {
+                return Err(Error::msg(format!(
+                    "Expected a single operation below columns {} to {}, found '{}'.",
+                    start + 1,
+                    col,
+                    symbols.iter().collect::<String>()
+                )));
+            }
+        };
+        let rows: Vec<Vec<char>> = numbers
+            .iter()
+            .map(|line| (start..col).map(|c| cell(line, c)).collect())
+            .collect();
+        blocks.push(Block { rows, operation });
+    }
+    Ok(blocks)
+}
+
+fn parse_number(digits: impl Iterator<Item = char>) -> Result<Option<u64>, Error> {
+    let digits: String = digits.filter(|&c| c != ' ').collect();
+    if digits.is_empty() {
+        return Ok(None);
     }
-    Ok(grand_total)
-}
-
-fn transpose_strings(homework: Vec<String>) -> Result<Vec<String>, Error> {
-    let ncols: usize = homework[0].len() + 1;
-    let mut transposed_homework: Vec<String> =
-        (0..ncols).map(|_| "".to_string()).collect::<Vec<String>>();
-    for line in homework {
-        for (col_index, c) in line.chars().enumerate() {
-            if ['+', '*'].contains(&c) {
-                transposed_homework[ncols - col_index - 1].push(c);
-            } else if c != ' ' {
-                transposed_homework[ncols - col_index - 2].push(c);
+    Ok(Some(digits.parse::<u64>()?))
+}
+
+/// Read every problem as one number per row.
+pub fn read_by_rows(homework: &[String]) -> Result<Vec<Expression>, Error> {
+    parse_worksheet(homework)?
+        .into_iter()
+        .map(|block| {
+            let operands: Vec<u64> = block
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(index, row)| {
+                    parse_number(row.iter().copied())?
+                        .ok_or(Error::msg(format!("Missing number on line {}.", index + 1)))
+                })
+                .collect::<Result<Vec<u64>, Error>>()?;
+            Ok(Expression {
+                operation: block.operation,
+                operands,
+            })
+        })
+        .collect()
+}
+
+/// Read every problem the cephalopod way: one number per column, with its digits from
+/// top to bottom, and the columns from right to left.
+pub fn read_by_columns(homework: &[String]) -> Result<Vec<Expression>, Error> {
+    parse_worksheet(homework)?
+        .into_iter()
+        .map(|block| {
+            let width: usize = block.rows.first().map_or(0, Vec::len);
+            let mut operands: Vec<u64> = Vec::new();
+            for col in (0..width).rev() {
+                // A column may only hold the operation.
+                if let Some(operand) = parse_number(block.rows.iter().map(|row| row[col]))? {
+                    operands.push(operand);
+                }
             }
-        }
+            Ok(Expression {
+                operation: block.operation,
+                operands,
+            })
+        })
+        .collect()
+}
+
+fn grand_total(problems: Vec<Expression>) -> Result<i128, Error> {
+    problems.iter().try_fold(0i128, |total, problem| {
+        total
+            .checked_add(problem.evaluate()?)
+            .ok_or(Error::msg("Grand total overflows."))
+    })
+}
+
+fn calculate_grand_total(homework: Vec<String>) -> Result<i128, Error> {
+    grand_total(read_by_rows(&homework)?)
+}
+
+fn calculate_grand_total_v2(homework: Vec<String>) -> Result<i128, Error> {
+    grand_total(read_by_columns(&homework)?)
+}
+
+/// Check that the worksheet can be read both by rows and by columns, with the same
+/// parser as the solvers.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    if let Err(error) = parse_worksheet(lines) {
+        return vec![InputProblem::global(error.to_string())];
     }
-    Ok(transposed_homework)
-}
-
-fn calculate_grand_total_v2(homework: Vec<String>) -> Result<usize, Error> {
-    let mut grand_total: usize = 0;
-    let mut factors: Vec<usize> = Vec::new();
-    for line in transpose_strings(homework)? {
-        if line == "+" {
-            grand_total += Array1::from_vec(factors).sum();
-            factors = Vec::new();
-        } else if line == "*" {
-            grand_total += Array1::from_vec(factors).product();
-            factors = Vec::new();
-        } else {
-            factors.push(line.parse::<usize>()?);
+    [read_by_rows(lines).err(), read_by_columns(lines).err()]
+        .into_iter()
+        .flatten()
+        .map(|error| InputProblem::global(error.to_string()))
+        .collect()
+}
+
+/// Generate a worksheet of `size` problems, with 2 to 4 numbers each.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    let num_rows: usize = rng.random_range(2..=4);
+    let mut lines: Vec<String> = vec![String::new(); num_rows + 1];
+    for problem_index in 0..size.max(1) {
+        let numbers: Vec<String> = (0..num_rows)
+            .map(|_| rng.random_range(1..10000).to_string())
+            .collect();
+        let width: usize = numbers.iter().map(|n| n.len()).max().unwrap_or(1);
+        let separator: &str = if problem_index == 0 { "" } else { " " };
+        for (line, number) in lines.iter_mut().zip(numbers) {
+            // Numbers are randomly aligned within their column.
+            let padding: usize = rng.random_range(0..=(width - number.len()));
+            line.push_str(&format!(
+                "{}{}{}{}",
+                separator,
+                " ".repeat(padding),
+                number,
+                " ".repeat(width - number.len() - padding)
+            ));
         }
+        let operation: char = if rng.random_bool(0.5) { '+' } else { '*' };
+        lines[num_rows].push_str(&format!("{}{:<w$}", separator, operation, w = width));
     }
-    Ok(grand_total)
+    lines
 }
 
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
-    let grand_total: usize = calculate_grand_total(read_lines(input)?)?;
+    let grand_total: i128 = calculate_grand_total(read_lines(input)?)?;
     tracing::info!("The grand total is {}.", grand_total);
     Ok(())
 }
 
 pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
-    let grand_total: usize = calculate_grand_total_v2(read_lines(input)?)?;
+    let grand_total: i128 = calculate_grand_total_v2(read_lines(input)?)?;
     tracing::info!("The grand total is {}.", grand_total);
     Ok(())
 }
@@ -107,6 +271,7 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
@@ -135,4 +300,109 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_worksheet() -> Result<(), Error> {
+        // Ragged lines, with trailing spaces trimmed.
+        let homework: Vec<String> = [
+            "123 328  51 64",
+            " 45 64  387 23",
+            "  6 98  215 314",
+            "-   /   *   +",
+            "",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let by_rows: Vec<Expression> = read_by_rows(&homework)?;
+        assert_eq!(
+            by_rows
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "123 - 45 - 6",
+                "328 / 64 / 98",
+                "51 * 387 * 215",
+                "64 + 23 + 314"
+            ]
+        );
+        assert_eq!(
+            by_rows
+                .iter()
+                .map(Expression::evaluate)
+                .collect::<Result<Vec<i128>, Error>>()?,
+            vec![72, 0, 4243455, 401]
+        );
+        let by_columns: Vec<Expression> = read_by_columns(&homework)?;
+        assert_eq!(
+            by_columns[0],
+            Expression {
+                operation: Operation::Subtract,
+                operands: vec![356, 24, 1]
+            }
+        );
+        assert_eq!(by_columns[1].operands, vec![8, 248, 369]);
+        assert_eq!(by_columns[3].operands, vec![4, 431, 623]);
+        assert_eq!(by_columns[0].evaluate()?, 331);
+
+        let divide_by_zero: Vec<String> = vec!["4 0".to_string(), "/  ".to_string()];
+        assert!(calculate_grand_total(divide_by_zero).is_err());
+        for invalid in [
+            vec!["1 2".to_string(), "+ %".to_string()],
+            vec!["12".to_string(), "++".to_string()],
+            vec!["1 2".to_string(), "+  ".to_string()],
+            vec!["1x".to_string(), "+".to_string()],
+            vec!["+".to_string()],
+            vec!["  ".to_string()],
+        ] {
+            assert!(
+                calculate_grand_total(invalid.clone()).is_err(),
+                "{:?}",
+                invalid
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_input() {
+        let to_lines =
+            |lines: &[&str]| -> Vec<String> { lines.iter().map(|line| line.to_string()).collect() };
+        // Trailing blank lines are fine.
+        assert_eq!(check_input(&to_lines(&["1 2", "+ *", ""])), vec![]);
+        assert_eq!(
+            check_input(&to_lines(&["12 3", "4  5", "+ -"])),
+            vec![InputProblem::global(
+                "Expected a single operation below columns 1 to 4, found '+-'."
+            )]
+        );
+        assert_eq!(
+            check_input(&to_lines(&["1 2", "3 x", "+ *"])),
+            vec![InputProblem::global("Invalid character 'x' on line 2.")]
+        );
+        assert_eq!(
+            check_input(&to_lines(&["1 2", "  3", "+ *"])),
+            vec![InputProblem::global("Missing number on line 2.")]
+        );
+        assert!(calculate_grand_total(to_lines(&["12 3", "4  5", "+ -"])).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_parsers_never_panic(homework in prop::collection::vec("\\PC{0,12}", 0..5)) {
+            let _ = calculate_grand_total(homework.clone());
+            let _ = calculate_grand_total_v2(homework);
+        }
+
+        #[test]
+        fn test_parsers_never_panic_near_valid(
+            homework in prop::collection::vec("[0-9 ]{0,8}|[-+*/ ]{0,8}", 0..5)
+        ) {
+            let is_valid: bool = check_input(&homework).is_empty();
+            prop_assert_eq!(read_by_rows(&homework).is_ok() && read_by_columns(&homework).is_ok(), is_valid);
+            let _ = calculate_grand_total(homework.clone());
+            let _ = calculate_grand_total_v2(homework);
+        }
+    }
 }
@@ -4,57 +4,52 @@ use std::{
 };
 
 use anyhow::{Error, Result};
+use rand::Rng;
 
+use crate::check::{InputProblem, check_grid};
+use crate::grid::{Grid, Position};
 use crate::io::read_lines;
+use crate::point::{Direction, Point2};
 
 struct ParsedMap {
-    pub source: (i64, i64),
-    pub splitters: HashSet<(i64, i64)>,
+    pub source: Point2,
+    pub splitters: HashSet<Point2>,
     pub nrows: usize,
 }
 
 fn parse_map(map: Vec<String>) -> Result<ParsedMap, Error> {
-    let mut source: Option<(i64, i64)> = None;
-    let mut splitters: HashSet<(i64, i64)> = HashSet::new();
-    for (row_index, row) in map.iter().enumerate() {
-        for (col_index, c) in row.chars().enumerate() {
-            match c {
-                'S' => {
-                    if source.is_some() {
-                        return Err(Error::msg("Found multiple sources."));
-                    }
-                    source = Some((i64::try_from(row_index)?, i64::try_from(col_index)?));
-                }
-                '^' => {
-                    splitters.insert((i64::try_from(row_index)?, i64::try_from(col_index)?));
-                }
-                '.' => {}
-                _ => {
-                    return Err(Error::msg(format!("Unrecognized character '{}'.", c)));
-                }
-            }
-        }
+    let grid: Grid<char> = Grid::parse_with(&map, |c| match c {
+        'S' | '^' | '.' => Ok(c),
+        _ => Err(Error::msg(format!("Unrecognized character '{}'.", c))),
+    })?;
+    let sources: Vec<Position> = grid.find_all(|&c| c == 'S');
+    if sources.len() > 1 {
+        return Err(Error::msg("Found multiple sources."));
     }
     Ok(ParsedMap {
-        source: source.ok_or(Error::msg("No source found."))?,
-        splitters,
-        nrows: map.len(),
+        source: Point2::try_from(*sources.first().ok_or(Error::msg("No source found."))?)?,
+        splitters: grid
+            .find_all(|&c| c == '^')
+            .into_iter()
+            .map(Point2::try_from)
+            .collect::<Result<HashSet<Point2>, Error>>()?,
+        nrows: grid.nrows(),
     })
 }
 
 fn count_splits(parsed_map: ParsedMap) -> Result<usize, Error> {
     let mut split_counter: usize = 0;
-    let mut rays_positions: HashSet<(i64, i64)> = HashSet::new();
+    let mut rays_positions: HashSet<Point2> = HashSet::new();
     rays_positions.insert(parsed_map.source);
 
     for _ in 0..parsed_map.nrows {
-        let mut current_rays_positions: HashSet<(i64, i64)> = HashSet::new();
+        let mut current_rays_positions: HashSet<Point2> = HashSet::new();
         for ray in rays_positions.iter() {
-            let new_position: (i64, i64) = (ray.0 + 1, ray.1);
+            let new_position: Point2 = *ray + Direction::South;
             if parsed_map.splitters.contains(&new_position) {
                 split_counter += 1;
-                current_rays_positions.insert((new_position.0, new_position.1 - 1));
-                current_rays_positions.insert((new_position.0, new_position.1 + 1));
+                current_rays_positions.insert(new_position + Direction::West);
+                current_rays_positions.insert(new_position + Direction::East);
             } else {
                 current_rays_positions.insert(new_position);
             }
@@ -64,14 +59,18 @@ fn count_splits(parsed_map: ParsedMap) -> Result<usize, Error> {
     Ok(split_counter)
 }
 
+fn timeline_overflow() -> Error {
+    Error::msg("Too many timelines to count.")
+}
+
 fn add_timeline(
-    mut trace: HashMap<(i64, i64), usize>,
-    k: (i64, i64),
+    mut trace: HashMap<Point2, usize>,
+    k: Point2,
     v0: usize,
-) -> Result<HashMap<(i64, i64), usize>, Error> {
+) -> Result<HashMap<Point2, usize>, Error> {
     match trace.get_mut(&k) {
         Some(v) => {
-            *v += v0;
+            *v = v.checked_add(v0).ok_or(timeline_overflow())?;
         }
         None => {
             trace.insert(k, v0);
@@ -81,22 +80,76 @@ fn add_timeline(
 }
 
 fn count_timelines(parsed_map: ParsedMap) -> Result<usize, Error> {
-    let mut rays_positions: HashMap<(i64, i64), usize> = HashMap::new();
+    let mut rays_positions: HashMap<Point2, usize> = HashMap::new();
     rays_positions.insert(parsed_map.source, 1);
     for _ in 0..parsed_map.nrows {
-        let mut trace: HashMap<(i64, i64), usize> = HashMap::new();
+        let mut trace: HashMap<Point2, usize> = HashMap::new();
         for (ray, &counter) in rays_positions.iter() {
-            let new_position: (i64, i64) = (ray.0 + 1, ray.1);
+            let new_position: Point2 = *ray + Direction::South;
             if parsed_map.splitters.contains(&new_position) {
-                trace = add_timeline(trace, (new_position.0, new_position.1 - 1), counter)?;
-                trace = add_timeline(trace, (new_position.0, new_position.1 + 1), counter)?;
+                trace = add_timeline(trace, new_position + Direction::West, counter)?;
+                trace = add_timeline(trace, new_position + Direction::East, counter)?;
             } else {
                 trace = add_timeline(trace, new_position, counter)?;
             }
         }
         rays_positions = trace;
     }
-    Ok(rays_positions.values().sum())
+    rays_positions
+        .values()
+        .try_fold(0usize, |total, &counter| total.checked_add(counter))
+        .ok_or(timeline_overflow())
+}
+
+/// Check that the input is a rectangular map with a single source.
+pub fn check_input(lines: &[String]) -> Vec<InputProblem> {
+    let mut problems: Vec<InputProblem> = check_grid(lines, ".^S");
+    let sources: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(index, line)| line.matches('S').map(move |_| index))
+        .collect();
+    match sources[..] {
+        [] => problems.push(InputProblem::global("no `S` source found")),
+        [_] => {}
+        [first, ..] => {
+            for &index in sources.iter().skip(1) {
+                problems.push(InputProblem::at(
+                    index,
+                    format!(
+                        "multiple `S` sources, the first one is on line {}",
+                        first + 1
+                    ),
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Generate a `size`x`size` map, with splitters on every other row.
+pub fn generate_input(size: usize, rng: &mut impl Rng) -> Vec<String> {
+    let width: usize = size.max(1);
+    (0..width)
+        .map(|row_index| {
+            (0..width)
+                .map(|col_index| {
+                    if row_index == 0 && col_index == width / 2 {
+                        'S'
+                    } else if row_index % 2 == 0
+                        && row_index > 0
+                        && col_index > 0
+                        && col_index + 1 < width
+                        && rng.random_bool(0.3)
+                    {
+                        '^'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
 }
 
 pub fn run_part_1(input: PathBuf) -> Result<(), Error> {
@@ -115,6 +168,7 @@ pub fn run_part_2(input: PathBuf) -> Result<(), Error> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_1() -> Result<(), Error> {
@@ -167,4 +221,34 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_check_input() {
+        assert_eq!(
+            check_input(&[
+                "..S..".to_string(),
+                ".....".to_string(),
+                "..^.S".to_string(),
+                "..^.".to_string(),
+            ]),
+            vec![
+                InputProblem::at(3, "ragged row, expected 5 columns but found 4"),
+                InputProblem::at(2, "multiple `S` sources, the first one is on line 1"),
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_map_never_panics(map in prop::collection::vec("\\PC{0,8}", 0..8)) {
+            let _ = parse_map(map);
+        }
+
+        #[test]
+        fn test_parse_map_never_panics_near_valid(map in prop::collection::vec("[.^S]{0,6}", 0..6)) {
+            let num_sources: usize = map.iter().map(|row| row.matches('S').count()).sum();
+            let is_rectangular: bool = map.iter().all(|row| row.len() == map[0].len());
+            prop_assert_eq!(parse_map(map).is_ok(), num_sources == 1 && is_rectangular);
+        }
+    }
 }
@@ -0,0 +1,376 @@
+/// An Advent of Code puzzle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Puzzle {
+    pub year: usize,
+    pub day: usize,
+    pub title: &'static str,
+    pub parts: usize,
+}
+
+impl Puzzle {
+    /// Release date, in `YYYY-MM-DD` format. Puzzles unlock at midnight EST (05:00 UTC).
+    pub fn release_date(&self) -> String {
+        format!("{}-12-{:02}", self.year, self.day)
+    }
+}
+
+const TITLES_2015: &[&str] = &[
+    "Not Quite Lisp",
+    "I Was Told There Would Be No Math",
+    "Perfectly Spherical Houses in a Vacuum",
+    "The Ideal Stocking Stuffer",
+    "Doesn't He Have Intern-Elves For This?",
+    "Probably a Fire Hazard",
+    "Some Assembly Required",
+    "Matchsticks",
+    "All in a Single Night",
+    "Elves Look, Elves Say",
+    "Corporate Policy",
+    "JSAbacusFramework.io",
+    "Knights of the Dinner Table",
+    "Reindeer Olympics",
+    "Science for Hungry People",
+    "Aunt Sue",
+    "No Such Thing as Too Much",
+    "Like a GIF For Your Yard",
+    "Medicine for Rudolph",
+    "Infinite Elves and Infinite Houses",
+    "RPG Simulator 20XX",
+    "Wizard Simulator 20XX",
+    "Opening the Turing Lock",
+    "It Hangs in the Balance",
+    "Let It Snow",
+];
+
+const TITLES_2016: &[&str] = &[
+    "No Time for a Taxicab",
+    "Bathroom Security",
+    "Squares With Three Sides",
+    "Security Through Obscurity",
+    "How About a Nice Game of Chess?",
+    "Signals and Noise",
+    "Internet Protocol Version 7",
+    "Two-Factor Authentication",
+    "Explosives in Cyberspace",
+    "Balance Bots",
+    "Radioisotope Thermoelectric Generators",
+    "Leonardo's Monorail",
+    "A Maze of Twisty Little Cubicles",
+    "One-Time Pad",
+    "Timing is Everything",
+    "Dragon Checksum",
+    "Two Steps Forward",
+    "Like a Rogue",
+    "An Elephant Named Joseph",
+    "Firewall Rules",
+    "Scrambled Letters and Hash",
+    "Grid Computing",
+    "Safe Cracking",
+    "Air Duct Spelunking",
+    "Clock Signal",
+];
+
+const TITLES_2017: &[&str] = &[
+    "Inverse Captcha",
+    "Corruption Checksum",
+    "Spiral Memory",
+    "High-Entropy Passphrases",
+    "A Maze of Twisty Trampolines, All Alike",
+    "Memory Reallocation",
+    "Recursive Circus",
+    "I Heard You Like Registers",
+    "Stream Processing",
+    "Knot Hash",
+    "Hex Ed",
+    "Digital Plumber",
+    "Packet Scanners",
+    "Disk Defragmentation",
+    "Dueling Generators",
+    "Permutation Promenade",
+    "Spinlock",
+    "Duet",
+    "A Series of Tubes",
+    "Particle Swarm",
+    "Fractal Art",
+    "Sporifica Virus",
+    "Coprocessor Conflagration",
+    "Electromagnetic Moat",
+    "The Halting Problem",
+];
+
+const TITLES_2018: &[&str] = &[
+    "Chronal Calibration",
+    "Inventory Management System",
+    "No Matter How You Slice It",
+    "Repose Record",
+    "Alchemical Reduction",
+    "Chronal Coordinates",
+    "The Sum of Its Parts",
+    "Memory Maneuver",
+    "Marble Mania",
+    "The Stars Align",
+    "Chronal Charge",
+    "Subterranean Sustainability",
+    "Mine Cart Madness",
+    "Chocolate Charts",
+    "Beverage Bandits",
+    "Chronal Classification",
+    "Reservoir Research",
+    "Settlers of The North Pole",
+    "Go With The Flow",
+    "A Regular Map",
+    "Chronal Conversion",
+    "Mode Maze",
+    "Experimental Emergency Teleportation",
+    "Immune System Simulator 20XX",
+    "Four-Dimensional Adventure",
+];
+
+const TITLES_2019: &[&str] = &[
+    "The Tyranny of the Rocket Equation",
+    "1202 Program Alarm",
+    "Crossed Wires",
+    "Secure Container",
+    "Sunny with a Chance of Asteroids",
+    "Universal Orbit Map",
+    "Amplification Circuit",
+    "Space Image Format",
+    "Sensor Boost",
+    "Monitoring Station",
+    "Space Police",
+    "The N-Body Problem",
+    "Care Package",
+    "Space Stoichiometry",
+    "Oxygen System",
+    "Flawed Frequency Transmission",
+    "Set and Forget",
+    "Many-Worlds Interpretation",
+    "Tractor Beam",
+    "Donut Maze",
+    "Springdroid Adventure",
+    "Slam Shuffle",
+    "Category Six",
+    "Planet of Discord",
+    "Cryostasis",
+];
+
+const TITLES_2020: &[&str] = &[
+    "Report Repair",
+    "Password Philosophy",
+    "Toboggan Trajectory",
+    "Passport Processing",
+    "Binary Boarding",
+    "Custom Customs",
+    "Handy Haversacks",
+    "Handheld Halting",
+    "Encoding Error",
+    "Adapter Array",
+    "Seating System",
+    "Rain Risk",
+    "Shuttle Search",
+    "Docking Data",
+    "Rambunctious Recitation",
+    "Ticket Translation",
+    "Conway Cubes",
+    "Operation Order",
+    "Monster Messages",
+    "Jurassic Jigsaw",
+    "Allergen Assessment",
+    "Crab Combat",
+    "Crab Cups",
+    "Lobby Layout",
+    "Combo Breaker",
+];
+
+const TITLES_2021: &[&str] = &[
+    "Sonar Sweep",
+    "Dive!",
+    "Binary Diagnostic",
+    "Giant Squid",
+    "Hydrothermal Venture",
+    "Lanternfish",
+    "The Treachery of Whales",
+    "Seven Segment Search",
+    "Smoke Basin",
+    "Syntax Scoring",
+    "Dumbo Octopus",
+    "Passage Pathing",
+    "Transparent Origami",
+    "Extended Polymerization",
+    "Chiton",
+    "Packet Decoder",
+    "Trick Shot",
+    "Snailfish",
+    "Beacon Scanner",
+    "Trench Map",
+    "Dirac Dice",
+    "Reactor Reboot",
+    "Amphipod",
+    "Arithmetic Logic Unit",
+    "Sea Cucumber",
+];
+
+const TITLES_2022: &[&str] = &[
+    "Calorie Counting",
+    "Rock Paper Scissors",
+    "Rucksack Reorganization",
+    "Camp Cleanup",
+    "Supply Stacks",
+    "Tuning Trouble",
+    "No Space Left On Device",
+    "Treetop Tree House",
+    "Rope Bridge",
+    "Cathode-Ray Tube",
+    "Monkey in the Middle",
+    "Hill Climbing Algorithm",
+    "Distress Signal",
+    "Regolith Reservoir",
+    "Beacon Exclusion Zone",
+    "Proboscidea Volcanium",
+    "Pyroclastic Flow",
+    "Boiling Boulders",
+    "Not Enough Minerals",
+    "Grove Positioning System",
+    "Monkey Math",
+    "Monkey Map",
+    "Unstable Diffusion",
+    "Blizzard Basin",
+    "Full of Hot Air",
+];
+
+const TITLES_2023: &[&str] = &[
+    "Trebuchet?!",
+    "Cube Conundrum",
+    "Gear Ratios",
+    "Scratchcards",
+    "If You Give A Seed A Fertilizer",
+    "Wait For It",
+    "Camel Cards",
+    "Haunted Wasteland",
+    "Mirage Maintenance",
+    "Pipe Maze",
+    "Cosmic Expansion",
+    "Hot Springs",
+    "Point of Incidence",
+    "Parabolic Reflector Dish",
+    "Lens Library",
+    "The Floor Will Be Lava",
+    "Clumsy Crucible",
+    "Lavaduct Lagoon",
+    "Aplenty",
+    "Pulse Propagation",
+    "Step Counter",
+    "Sand Slabs",
+    "A Long Walk",
+    "Never Tell Me The Odds",
+    "Snowverload",
+];
+
+const TITLES_2024: &[&str] = &[
+    "Historian Hysteria",
+    "Red-Nosed Reports",
+    "Mull It Over",
+    "Ceres Search",
+    "Print Queue",
+    "Guard Gallivant",
+    "Bridge Repair",
+    "Resonant Collinearity",
+    "Disk Fragmenter",
+    "Hoof It",
+    "Plutonian Pebbles",
+    "Garden Groups",
+    "Claw Contraption",
+    "Restroom Redoubt",
+    "Warehouse Woes",
+    "Reindeer Maze",
+    "Chronospatial Computer",
+    "RAM Run",
+    "Linen Layout",
+    "Race Condition",
+    "Keypad Conundrum",
+    "Monkey Market",
+    "LAN Party",
+    "Crossed Wires",
+    "Code Chronicle",
+];
+
+const TITLES_2025: &[&str] = &[
+    "Secret Entrance",
+    "Gift Shop",
+    "Lobby",
+    "Printing Department",
+    "Cafeteria",
+    "Trash Compactor",
+    "Laboratories",
+    "Playground",
+    "Movie Theater",
+    "Factory",
+    "Reactor",
+    "Christmas Tree Farm",
+];
+
+const TITLES: &[(usize, &[&str])] = &[
+    (2015, TITLES_2015),
+    (2016, TITLES_2016),
+    (2017, TITLES_2017),
+    (2018, TITLES_2018),
+    (2019, TITLES_2019),
+    (2020, TITLES_2020),
+    (2021, TITLES_2021),
+    (2022, TITLES_2022),
+    (2023, TITLES_2023),
+    (2024, TITLES_2024),
+    (2025, TITLES_2025),
+];
+
+/// All Advent of Code years.
+pub fn years() -> impl Iterator<Item = usize> {
+    TITLES.iter().map(|(year, _)| *year)
+}
+
+/// All puzzles of a year. The last day only has one part.
+pub fn puzzles(year: usize) -> impl Iterator<Item = Puzzle> {
+    let titles: &[&str] = TITLES
+        .iter()
+        .find(|(y, _)| *y == year)
+        .map(|(_, titles)| *titles)
+        .unwrap_or_default();
+    titles.iter().enumerate().map(move |(index, title)| Puzzle {
+        year,
+        day: index + 1,
+        title,
+        parts: if index + 1 == titles.len() { 1 } else { 2 },
+    })
+}
+
+/// Look up a single puzzle.
+pub fn puzzle(year: usize, day: usize) -> Option<Puzzle> {
+    puzzles(year).find(|p| p.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_puzzles() {
+        assert_eq!(years().count(), 11);
+        for year in years() {
+            let expected_days: usize = if year < 2025 { 25 } else { 12 };
+            assert_eq!(puzzles(year).count(), expected_days);
+        }
+        assert_eq!(
+            puzzle(2015, 6),
+            Some(Puzzle {
+                year: 2015,
+                day: 6,
+                title: "Probably a Fire Hazard",
+                parts: 2
+            })
+        );
+        assert_eq!(puzzle(2025, 12).map(|p| p.parts), Some(1));
+        assert_eq!(puzzle(2025, 13), None);
+        assert_eq!(puzzle(2025, 1).unwrap().release_date(), "2025-12-01");
+    }
+}
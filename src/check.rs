@@ -0,0 +1,134 @@
+use std::fmt;
+
+/// A problem found in a puzzle input.
+#[derive(Debug, PartialEq)]
+pub struct InputProblem {
+    /// Base-1 line number, if the problem is tied to a line.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl InputProblem {
+    /// A problem on a line, given its base-0 index.
+    pub fn at(line_index: usize, message: impl Into<String>) -> InputProblem {
+        InputProblem {
+            line: Some(line_index + 1),
+            message: message.into(),
+        }
+    }
+
+    /// A problem with the input as a whole.
+    pub fn global(message: impl Into<String>) -> InputProblem {
+        InputProblem {
+            line: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for InputProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Check every line, reporting the messages returned by `check`.
+pub fn check_each_line(
+    lines: &[String],
+    check: impl Fn(&str) -> Option<String>,
+) -> Vec<InputProblem> {
+    let mut problems: Vec<InputProblem> = Vec::new();
+    if lines.is_empty() {
+        problems.push(InputProblem::global("input is empty"));
+    }
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(message) = check(line) {
+            problems.push(InputProblem::at(index, message));
+        }
+    }
+    problems
+}
+
+/// Check that the input is a single line, and check that line.
+pub fn check_single_line(
+    lines: &[String],
+    check: impl Fn(&str) -> Option<String>,
+) -> Vec<InputProblem> {
+    let mut problems: Vec<InputProblem> = check_each_line(lines, check);
+    if lines.len() > 1 {
+        problems.push(InputProblem::global(format!(
+            "expected a single line, found {}",
+            lines.len()
+        )));
+    }
+    problems
+}
+
+/// Report the first character that is not in `allowed`, if any.
+pub fn check_charset(line: &str, allowed: &str) -> Option<String> {
+    line.chars()
+        .enumerate()
+        .find(|(_, c)| !allowed.contains(*c))
+        .map(|(col, c)| format!("unexpected character '{}' at column {}", c, col + 1))
+}
+
+/// Check that a character map is rectangular and only uses `allowed` characters.
+pub fn check_grid(lines: &[String], allowed: &str) -> Vec<InputProblem> {
+    let width: usize = lines.first().map(|l| l.chars().count()).unwrap_or(0);
+    let mut problems: Vec<InputProblem> = check_each_line(lines, |line| {
+        let line_width: usize = line.chars().count();
+        if line_width != width {
+            Some(format!(
+                "ragged row, expected {} columns but found {}",
+                width, line_width
+            ))
+        } else {
+            check_charset(line, allowed)
+        }
+    });
+    if width == 0 && !lines.is_empty() {
+        problems.push(InputProblem::global("map has no columns"));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn to_lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            InputProblem::at(16, "expected `NxNxN`").to_string(),
+            "line 17: expected `NxNxN`"
+        );
+        assert_eq!(
+            InputProblem::global("input is empty").to_string(),
+            "input is empty"
+        );
+    }
+
+    #[test]
+    fn test_check_grid() {
+        assert_eq!(check_grid(&to_lines(&["..@", "@@."]), ".@"), vec![]);
+        assert_eq!(
+            check_grid(&to_lines(&["..@", "@@", "@x."]), ".@"),
+            vec![
+                InputProblem::at(1, "ragged row, expected 3 columns but found 2"),
+                InputProblem::at(2, "unexpected character 'x' at column 2"),
+            ]
+        );
+        assert_eq!(
+            check_grid(&[], ".@"),
+            vec![InputProblem::global("input is empty")]
+        );
+    }
+}
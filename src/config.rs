@@ -0,0 +1,175 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+/// Name of the project configuration file.
+pub const CONFIG_FILE_NAME: &str = ".ra.toml";
+
+/// Format of the log lines printed by the runner.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Full,
+    Compact,
+    Pretty,
+}
+
+/// Benchmark settings.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchConfig {
+    pub warmup: Option<usize>,
+    pub iterations: Option<usize>,
+}
+
+/// Runner settings, read from a `.ra.toml` file and/or the command line.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Root folder of the puzzle inputs, laid out as `<year>/day<day>.txt`.
+    pub inputs: Option<PathBuf>,
+    pub output: Option<OutputFormat>,
+    /// Log filter, in `tracing_subscriber::EnvFilter` syntax.
+    pub log: Option<String>,
+    /// Solver timeout, in seconds.
+    pub timeout: Option<u64>,
+    /// Path to the file with the verified answers.
+    pub answers: Option<PathBuf>,
+    pub bench: BenchConfig,
+}
+
+impl Config {
+    pub const DEFAULT_LOG: &str = "info";
+    pub const DEFAULT_WARMUP: usize = 1;
+    pub const DEFAULT_ITERATIONS: usize = 10;
+
+    /// Parse a configuration from its TOML content.
+    pub fn parse(content: &str) -> Result<Config, Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Load a configuration file, resolving its paths relative to the file location.
+    pub fn load(path: &Path) -> Result<Config, Error> {
+        let content: String = read_to_string(path)
+            .map_err(|e| Error::msg(format!("Cannot read {}: {}", path.display(), e)))?;
+        let mut config: Config = Config::parse(&content)
+            .map_err(|e| Error::msg(format!("Invalid config {}: {}", path.display(), e)))?;
+        let root: &Path = path.parent().unwrap_or(Path::new("."));
+        config.inputs = config.inputs.map(|p| root.join(p));
+        config.answers = config.answers.map(|p| root.join(p));
+        Ok(config)
+    }
+
+    /// Find the closest configuration file in `start` or any of its ancestors.
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Load the explicitly requested configuration file, or the discovered one if any.
+    pub fn resolve(explicit: Option<&Path>, start: &Path) -> Result<Config, Error> {
+        match explicit {
+            Some(path) => Config::load(path),
+            None => match Config::discover(start) {
+                Some(path) => Config::load(&path),
+                None => Ok(Config::default()),
+            },
+        }
+    }
+
+    /// Overlay the settings of `other`, which take precedence when set.
+    pub fn overridden_by(self, other: Config) -> Config {
+        Config {
+            inputs: other.inputs.or(self.inputs),
+            output: other.output.or(self.output),
+            log: other.log.or(self.log),
+            timeout: other.timeout.or(self.timeout),
+            answers: other.answers.or(self.answers),
+            bench: BenchConfig {
+                warmup: other.bench.warmup.or(self.bench.warmup),
+                iterations: other.bench.iterations.or(self.bench.iterations),
+            },
+        }
+    }
+
+    /// Default input location of a day, if an inputs root is configured.
+    pub fn input_path(&self, year: usize, day: usize) -> Option<PathBuf> {
+        self.inputs
+            .as_ref()
+            .map(|root| root.join(year.to_string()).join(format!("day{}.txt", day)))
+    }
+
+    pub fn log_filter(&self) -> &str {
+        self.log.as_deref().unwrap_or(Config::DEFAULT_LOG)
+    }
+
+    pub fn timeout_duration(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    pub fn warmup(&self) -> usize {
+        self.bench.warmup.unwrap_or(Config::DEFAULT_WARMUP)
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.bench.iterations.unwrap_or(Config::DEFAULT_ITERATIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() -> Result<(), Error> {
+        let config: Config = Config::parse(
+            "inputs = \"data\"\noutput = \"compact\"\nlog = \"debug\"\ntimeout = 30\nanswers = \"answers.toml\"\n\n[bench]\niterations = 5\n",
+        )?;
+        assert_eq!(config.inputs, Some(PathBuf::from("data")));
+        assert_eq!(config.output, Some(OutputFormat::Compact));
+        assert_eq!(config.log_filter(), "debug");
+        assert_eq!(config.timeout_duration(), Some(Duration::from_secs(30)));
+        assert_eq!(config.warmup(), Config::DEFAULT_WARMUP);
+        assert_eq!(config.iterations(), 5);
+        assert!(Config::parse("unknown = 1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overridden_by() -> Result<(), Error> {
+        let file: Config = Config::parse("log = \"debug\"\ntimeout = 30\n")?;
+        let cli: Config = Config {
+            timeout: Some(5),
+            ..Config::default()
+        };
+        let config: Config = file.overridden_by(cli);
+        assert_eq!(config.log_filter(), "debug");
+        assert_eq!(config.timeout, Some(5));
+        assert_eq!(config.input_path(2025, 3), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_and_load() -> Result<(), Error> {
+        let dir: tempfile::TempDir = tempfile::TempDir::new()?;
+        let root: PathBuf = dir.path().to_path_buf();
+        let nested: PathBuf = root.join("a").join("b");
+        std::fs::create_dir_all(&nested)?;
+        std::fs::write(root.join(CONFIG_FILE_NAME), "inputs = \"inputs\"\n")?;
+
+        let config_path: PathBuf = Config::discover(&nested).ok_or(Error::msg("Not found."))?;
+        assert_eq!(config_path, root.join(CONFIG_FILE_NAME));
+        assert_eq!(
+            Config::resolve(None, &nested)?.input_path(2015, 6),
+            Some(root.join("inputs").join("2015").join("day6.txt"))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+
+/// A cycle in the sequence `x0`, `x1 = step(x0)`, `x2 = step(x1)`, ...:
+/// every state from index `start` repeats `length` steps later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Index of the earlier state equal to state `n`, which is `n` itself before the cycle.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare: find the cycle in constant memory.
+/// `step` must be deterministic, and the sequence must eventually repeat.
+pub fn floyd<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise: S = step(&initial);
+    let mut hare: S = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // The distance between them is now a multiple of the length: restart the
+    // tortoise from the beginning, and they meet at the start of the cycle.
+    let mut start: usize = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length: usize = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Brent's algorithm: find the cycle in constant memory, with fewer steps than Floyd.
+/// `step` must be deterministic, and the sequence must eventually repeat.
+pub fn brent<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length by teleporting the tortoise to the hare at powers of two.
+    let mut power: usize = 1;
+    let mut length: usize = 1;
+    let mut tortoise: S = initial.clone();
+    let mut hare: S = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare `length` steps ahead, they meet at the start of the cycle.
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start: usize = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// A 64-bit hash of a state, to remember large states like grids cheaply.
+/// Distinct states may collide, although it is very unlikely.
+pub fn fingerprint<S: Hash>(state: &S) -> u64 {
+    let mut hasher: DefaultHasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The state after `n` steps from `initial`, skipping whole cycles once a state repeats.
+pub fn simulate_until<S: Clone + Eq + Hash>(initial: S, n: usize, step: impl FnMut(&S) -> S) -> S {
+    simulate_until_by(initial, n, step, S::clone)
+}
+
+/// Like `simulate_until`, but only remembering the `key` of every state,
+/// such as its `fingerprint`.
+pub fn simulate_until_by<S, K: Eq + Hash>(
+    initial: S,
+    n: usize,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> S {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut state: S = initial;
+    let mut index: usize = 0;
+    while index < n {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let length: usize = index - entry.get();
+                for _ in 0..(n - index) % length {
+                    state = step(&state);
+                }
+                return state;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(index);
+            }
+        }
+        state = step(&state);
+        index += 1;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    /// One generation of the game of life, on a torus.
+    fn life(grid: &Grid<bool>) -> Grid<bool> {
+        let (nrows, ncols) = (grid.nrows(), grid.ncols());
+        grid.map_indexed(|(row, col), &alive| {
+            let neighbours: usize = [nrows - 1, 0, 1]
+                .iter()
+                .flat_map(|dr| [ncols - 1, 0, 1].map(|dc| (dr, dc)))
+                .filter(|&(&dr, dc)| (dr, dc) != (0, 0))
+                .filter(|&(dr, dc)| grid[((row + dr) % nrows, (col + dc) % ncols)])
+                .count();
+            neighbours == 3 || (alive && neighbours == 2)
+        })
+    }
+
+    #[test]
+    fn test_glider() -> Result<(), anyhow::Error> {
+        let lines: Vec<String> = [".#....", "..#...", "###...", "......", "......", "......"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let glider: Grid<bool> = Grid::parse_with(&lines, |c| Ok(c == '#'))?;
+        // The glider is back where it started after 4 generations per cell of the torus.
+        assert_eq!(
+            floyd(glider.clone(), life),
+            Cycle {
+                start: 0,
+                length: 24
+            }
+        );
+        assert_eq!(
+            brent(glider.clone(), life),
+            Cycle {
+                start: 0,
+                length: 24
+            }
+        );
+        let expected: Grid<bool> = (0..1_000_000_007 % 24).fold(glider.clone(), |g, _| life(&g));
+        assert_eq!(
+            simulate_until(glider.clone(), 1_000_000_007, life),
+            expected
+        );
+        assert_eq!(
+            simulate_until_by(glider, 1_000_000_007, life, fingerprint),
+            expected
+        );
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_detectors_agree(modulus in 1..500u64, a in 0..500u64, c in 0..500u64, seed in 0..500u64, n in 0..2000usize) {
+            let step = |&x: &u64| (a * x + c) % modulus;
+            let cycle: Cycle = floyd(seed % modulus, step);
+            prop_assert_eq!(brent(seed % modulus, step), cycle);
+
+            let sequence: Vec<u64> = std::iter::successors(Some(seed % modulus), |x| Some(step(x)))
+                .take(n + 1)
+                .collect();
+            prop_assert_eq!(simulate_until(seed % modulus, n, step), sequence[n]);
+            prop_assert_eq!(sequence[cycle.reduce(n)], sequence[n]);
+        }
+    }
+}
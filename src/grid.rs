@@ -0,0 +1,285 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use anyhow::{Error, Result};
+use ndarray::{Array2, ArrayView2, ArrayViewMut2, s};
+
+/// Position of a cell, as `(row, col)`.
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid of cells, backed by an `Array2`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T>(Array2<T>);
+
+impl Grid<char> {
+    /// Parse a character map, one line per row.
+    pub fn parse(lines: &[String]) -> Result<Grid<char>, Error> {
+        Grid::parse_with(lines, Ok)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parse a map one line per row, converting every character with `parse_cell`.
+    pub fn parse_with(
+        lines: &[String],
+        parse_cell: impl Fn(char) -> Result<T, Error>,
+    ) -> Result<Grid<T>, Error> {
+        let ncols: usize = lines
+            .first()
+            .ok_or(Error::msg("Empty grid."))?
+            .chars()
+            .count();
+        let mut cells: Vec<T> = Vec::with_capacity(lines.len() * ncols);
+        for (row_index, line) in lines.iter().enumerate() {
+            let line_width: usize = line.chars().count();
+            if line_width != ncols {
+                return Err(Error::msg(format!(
+                    "Ragged row {}, expected {} columns but found {}.",
+                    row_index + 1,
+                    ncols,
+                    line_width
+                )));
+            }
+            for c in line.chars() {
+                cells.push(parse_cell(c)?);
+            }
+        }
+        Ok(Grid(Array2::from_shape_vec((lines.len(), ncols), cells)?))
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.0.nrows()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.0.ncols()
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.0 < self.nrows() && pos.1 < self.ncols()
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.0.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        self.0.get_mut(pos)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter()
+    }
+
+    /// Iterate over the cells with their position, row by row.
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.0.indexed_iter()
+    }
+
+    fn offset_neighbours(
+        &self,
+        pos: Position,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + '_ {
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let neighbour: Position =
+                (pos.0.checked_add_signed(dr)?, pos.1.checked_add_signed(dc)?);
+            self.contains(neighbour).then_some(neighbour)
+        })
+    }
+
+    /// Positions of the up to 4 orthogonal neighbours within the grid.
+    pub fn neighbours4(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offset_neighbours(pos, &OFFSETS_4)
+    }
+
+    /// Positions of the up to 8 orthogonal and diagonal neighbours within the grid.
+    pub fn neighbours8(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offset_neighbours(pos, &OFFSETS_8)
+    }
+
+    /// Position of the first cell matching `predicate`, row by row.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.indexed_iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Positions of all the cells matching `predicate`, row by row.
+    pub fn find_all(&self, predicate: impl Fn(&T) -> bool) -> Vec<Position> {
+        self.indexed_iter()
+            .filter(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// A new grid of the same shape, from every cell and its position.
+    pub fn map_indexed<U>(&self, f: impl Fn(Position, &T) -> U) -> Grid<U> {
+        Grid(Array2::from_shape_fn(self.0.raw_dim(), |pos| {
+            f(pos, &self.0[pos])
+        }))
+    }
+
+    pub fn view(&self) -> ArrayView2<'_, T> {
+        self.0.view()
+    }
+
+    pub fn view_mut(&mut self) -> ArrayViewMut2<'_, T> {
+        self.0.view_mut()
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.0
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `nrows`x`ncols` grid filled with `value`.
+    pub fn from_elem(nrows: usize, ncols: usize, value: T) -> Grid<T> {
+        Grid(Array2::from_elem((nrows, ncols), value))
+    }
+
+    pub fn transpose(&self) -> Grid<T> {
+        Grid(self.0.t().to_owned())
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        Grid(self.0.t().slice(s![.., ..;-1]).to_owned())
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Grid<T> {
+        Grid(self.0.t().slice(s![..;-1, ..]).to_owned())
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        Grid(self.0.slice(s![.., ..;-1]).to_owned())
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        Grid(self.0.slice(s![..;-1, ..]).to_owned())
+    }
+}
+
+impl<T> From<Array2<T>> for Grid<T> {
+    fn from(cells: Array2<T>) -> Grid<T> {
+        Grid(cells)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &T {
+        &self.0[pos]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut T {
+        &mut self.0[pos]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row_index, row) in self.0.rows().into_iter().enumerate() {
+            if row_index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn to_lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Error> {
+        let grid: Grid<char> = Grid::parse(&to_lines(&["ab.", "..S"]))?;
+        assert_eq!((grid.nrows(), grid.ncols()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'S');
+        assert_eq!(grid.to_string(), "ab.\n..S");
+        assert_eq!(
+            Grid::parse(&to_lines(&["ab.", ".S"]))
+                .unwrap_err()
+                .to_string(),
+            "Ragged row 2, expected 3 columns but found 2."
+        );
+        assert!(Grid::parse(&[]).is_err());
+        let digits: Grid<u32> = Grid::parse_with(&to_lines(&["12", "34"]), |c| {
+            c.to_digit(10).ok_or(Error::msg("Not a digit."))
+        })?;
+        assert_eq!(digits.iter().sum::<u32>(), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours() -> Result<(), Error> {
+        let grid: Grid<char> = Grid::parse(&to_lines(&["...", "...", "..."]))?;
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbours8((0, 2)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours4((2, 1)).count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find() -> Result<(), Error> {
+        let grid: Grid<char> = Grid::parse(&to_lines(&[".#.", "#.S"]))?;
+        assert_eq!(grid.find(|&c| c == 'S'), Some((1, 2)));
+        assert_eq!(grid.find(|&c| c == 'x'), None);
+        assert_eq!(grid.find_all(|&c| c == '#'), vec![(0, 1), (1, 0)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transformations() -> Result<(), Error> {
+        let grid: Grid<char> = Grid::parse(&to_lines(&["ab", "cd", "ef"]))?;
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "eca\nfdb");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "bdf\nace");
+        assert_eq!(grid.flip_horizontal().to_string(), "ba\ndc\nfe");
+        assert_eq!(grid.flip_vertical().to_string(), "ef\ncd\nab");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_counterclockwise(),
+            grid.clone()
+        );
+        assert_eq!(grid.view().column(1).to_vec(), vec!['b', 'd', 'f']);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(lines in prop::collection::vec("\\PC{0,8}", 0..8)) {
+            let _ = Grid::parse(&lines);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+use md5::{Context, Digest};
+
+/// Counters hashed by a thread before it claims the next chunk.
+const CHUNK_SIZE: u64 = 4096;
+
+/// Whether the hex form of `digest` starts with `zeros` zeros, read from the bytes.
+pub fn has_leading_zeros(digest: &Digest, zeros: usize) -> bool {
+    let bytes: &[u8; 16] = &digest.0;
+    zeros <= 32
+        && bytes[..zeros / 2].iter().all(|&b| b == 0)
+        && (zeros.is_multiple_of(2) || bytes[zeros / 2] >> 4 == 0)
+}
+
+/// Write the decimal digits of `n` at the end of `buffer`, and return them.
+fn decimal(mut n: u64, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut start: usize = buffer.len();
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buffer[start..];
+        }
+    }
+}
+
+/// Lowest counter `n` such that the MD5 of `prefix` followed by `n` in decimal
+/// satisfies `predicate`, using every available core. Counters go up to, but not
+/// including, `u64::MAX`, so the search always ends, with `None` when nothing matches.
+pub fn find_first_nonce(prefix: &str, predicate: impl Fn(&Digest) -> bool + Sync) -> Option<u64> {
+    find_next_nonce(prefix, 0, predicate)
+}
+
+/// Like `find_first_nonce`, but only considering counters from `start`,
+/// to list successive matches.
+pub fn find_next_nonce(
+    prefix: &str,
+    start: u64,
+    predicate: impl Fn(&Digest) -> bool + Sync,
+) -> Option<u64> {
+    let mut base: Context = Context::new();
+    base.consume(prefix);
+    let next_chunk: AtomicU64 = AtomicU64::new(0);
+    // `u64::MAX` until a match is found, which is why it is never a counter.
+    let best: AtomicU64 = AtomicU64::new(u64::MAX);
+    let num_threads: usize = thread::available_parallelism().map_or(1, |n| n.get());
+
+    thread::scope(|scope| {
+        for _ in 0..num_threads {
+            scope.spawn(|| {
+                let mut buffer: [u8; 20] = [0; 20];
+                loop {
+                    // Chunks are claimed in increasing order, so once a chunk starts
+                    // past the best match, every lower counter has been checked.
+                    let Some(chunk_start) = next_chunk
+                        .fetch_add(1, Ordering::Relaxed)
+                        .checked_mul(CHUNK_SIZE)
+                        .and_then(|offset| start.checked_add(offset))
+                    else {
+                        return;
+                    };
+                    if chunk_start >= best.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    for n in chunk_start..chunk_start.saturating_add(CHUNK_SIZE) {
+                        let mut context: Context = base.clone();
+                        context.consume(decimal(n, &mut buffer));
+                        if predicate(&context.finalize()) {
+                            best.fetch_min(n, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+    });
+    let best: u64 = best.into_inner();
+    (best < u64::MAX).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_find_nonce() {
+        let three_zeros = |digest: &Digest| has_leading_zeros(digest, 3);
+        let first: u64 = find_first_nonce("abc", three_zeros).unwrap();
+        assert!(format!("{:x}", md5::compute(format!("abc{}", first))).starts_with("000"));
+        assert!((0..first).all(|n| !three_zeros(&md5::compute(format!("abc{}", n)))));
+        let next: u64 = find_next_nonce("abc", first + 1, three_zeros).unwrap();
+        assert!(next > first && three_zeros(&md5::compute(format!("abc{}", next))));
+        assert_eq!(find_next_nonce("abc", next, three_zeros), Some(next));
+        // The end of the counters stops a search that never matches.
+        assert_eq!(find_next_nonce("abc", u64::MAX - 10_000, |_| false), None);
+        assert_eq!(
+            find_next_nonce("abc", u64::MAX - 5, |_| true),
+            Some(u64::MAX - 5)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_has_leading_zeros_matches_hex(bytes in any::<[u8; 16]>(), zeros in 0..34usize) {
+            let digest: Digest = Digest(bytes);
+            let hex: String = format!("{:x}", digest);
+            prop_assert_eq!(has_leading_zeros(&digest, zeros), hex.starts_with(&"0".repeat(zeros)));
+        }
+
+        #[test]
+        fn test_decimal(n in any::<u64>()) {
+            let mut buffer: [u8; 20] = [0; 20];
+            let expected: String = n.to_string();
+            prop_assert_eq!(decimal(n, &mut buffer), expected.as_bytes());
+        }
+    }
+}
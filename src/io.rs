@@ -6,18 +6,6 @@ use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Read;
 use std::path::PathBuf;
-use utf8_decode::UnsafeDecoder;
-
-/// Get all chars from a file content. Supports GZ compression.
-pub fn read_chars(input: PathBuf) -> Result<Vec<char>, Error> {
-    Ok(if input.to_str().unwrap_or(".").ends_with(".gz") {
-        UnsafeDecoder::new(BufReader::new(GzDecoder::new(File::open(input)?)).bytes())
-            .map(|r| r.unwrap())
-            .collect()
-    } else {
-        read_to_string(input)?.chars().collect()
-    })
-}
 
 /// Get all lines from a file content. Supports GZ compression.
 pub fn read_lines(input: PathBuf) -> Result<Vec<String>, Error> {
@@ -44,3 +32,10 @@ pub fn read(input: PathBuf) -> Result<String, Error> {
         read_to_string(input)?
     })
 }
+
+/// Get the content of a single-line file, without its line ending. Supports GZ compression.
+pub fn read_line(input: PathBuf) -> Result<String, Error> {
+    let mut content: String = read(input)?;
+    content.truncate(content.trim_end_matches(['\n', '\r']).len());
+    Ok(content)
+}
@@ -1,2 +1,20 @@
+pub mod answers;
 pub mod calendar;
+pub mod catalog;
+pub mod check;
+pub mod config;
+pub mod cycle;
+pub mod grid;
+pub mod hashing;
 pub mod io;
+pub mod linalg;
+pub mod math;
+pub mod ocr;
+pub mod pattern;
+pub mod point;
+pub mod progress;
+pub mod range_set;
+pub mod rectangles;
+pub mod search;
+pub mod variants;
+pub mod vm;
@@ -0,0 +1,236 @@
+use std::ops::RangeInclusive;
+
+use anyhow::{Error, Result};
+use itertools::Itertools;
+use nalgebra::{DMatrix, DVector};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
+
+/// All the solutions of a linear system `A x = b`, computed with exact rationals:
+/// `particular + sum(t_i * basis[i])` for any values `t_i` of the free variables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solutions {
+    /// The solution with every free variable set to zero.
+    pub particular: DVector<BigRational>,
+    /// Index of every free variable.
+    pub free_variables: Vec<usize>,
+    /// One direction per free variable, where it is 1 and the other free variables 0.
+    pub basis: Vec<DVector<BigRational>>,
+}
+
+fn rational(value: i64) -> BigRational {
+    BigRational::from_integer(BigInt::from(value))
+}
+
+/// Solve `A x = b` by Gaussian elimination over the rationals, so that round-off
+/// never matters. Returns `None` when the system is inconsistent, and an error when
+/// there is not one right-hand side value per equation.
+pub fn solve_exact(a: &DMatrix<i64>, b: &DVector<i64>) -> Result<Option<Solutions>, Error> {
+    let (nrows, ncols) = a.shape();
+    if b.len() != nrows {
+        return Err(Error::msg(format!(
+            "Expected {} right-hand side values, one per equation, found {}.",
+            nrows,
+            b.len()
+        )));
+    }
+    let mut m: DMatrix<BigRational> = DMatrix::from_fn(nrows, ncols + 1, |row, col| {
+        rational(if col < ncols { a[(row, col)] } else { b[row] })
+    });
+
+    // Reduce to reduced row echelon form, remembering the pivot column of every row.
+    let mut pivots: Vec<usize> = Vec::new();
+    for col in 0..ncols {
+        let row: usize = pivots.len();
+        let Some(pivot_row) = (row..nrows).find(|&r| !m[(r, col)].is_zero()) else {
+            continue;
+        };
+        m.swap_rows(row, pivot_row);
+        let pivot: BigRational = m[(row, col)].clone();
+        for c in col..=ncols {
+            m[(row, c)] = &m[(row, c)] / &pivot;
+        }
+        let pivot_values: Vec<BigRational> = m.row(row).iter().cloned().collect();
+        for r in (0..nrows).filter(|&r| r != row) {
+            let factor: BigRational = m[(r, col)].clone();
+            if factor.is_zero() {
+                continue;
+            }
+            for c in col..=ncols {
+                m[(r, c)] -= &factor * &pivot_values[c];
+            }
+        }
+        pivots.push(col);
+    }
+    // A zero row with a non-zero right-hand side reads `0 = c`.
+    if (pivots.len()..nrows).any(|r| !m[(r, ncols)].is_zero()) {
+        return Ok(None);
+    }
+
+    let mut particular: DVector<BigRational> = DVector::from_element(ncols, BigRational::zero());
+    for (row, &col) in pivots.iter().enumerate() {
+        particular[col] = m[(row, ncols)].clone();
+    }
+    let free_variables: Vec<usize> = (0..ncols).filter(|col| !pivots.contains(col)).collect();
+    let basis: Vec<DVector<BigRational>> = free_variables
+        .iter()
+        .map(|&free| {
+            let mut direction: DVector<BigRational> =
+                DVector::from_element(ncols, BigRational::zero());
+            direction[free] = rational(1);
+            for (row, &col) in pivots.iter().enumerate() {
+                direction[col] = -m[(row, free)].clone();
+            }
+            direction
+        })
+        .collect();
+    Ok(Some(Solutions {
+        particular,
+        free_variables,
+        basis,
+    }))
+}
+
+impl Solutions {
+    /// The only solution, if there are no free variables.
+    pub fn unique(&self) -> Option<&DVector<BigRational>> {
+        self.free_variables.is_empty().then_some(&self.particular)
+    }
+
+    /// The solution for given values of the free variables, if it is integral. Fails
+    /// unless there is one value per free variable.
+    pub fn integer_solution(&self, free_values: &[i64]) -> Result<Option<Vec<i64>>, Error> {
+        if free_values.len() != self.free_variables.len() {
+            return Err(Error::msg(format!(
+                "Expected {} values, one per free variable, found {}.",
+                self.free_variables.len(),
+                free_values.len()
+            )));
+        }
+        Ok(self.integer_solution_unchecked(free_values))
+    }
+
+    /// `integer_solution`, trusting that there is one value per free variable.
+    fn integer_solution_unchecked(&self, free_values: &[i64]) -> Option<Vec<i64>> {
+        let mut x: DVector<BigRational> = self.particular.clone();
+        for (direction, &value) in self.basis.iter().zip(free_values) {
+            x += direction * rational(value);
+        }
+        x.iter()
+            .map(|value| value.is_integer().then(|| value.to_integer().to_i64())?)
+            .collect()
+    }
+
+    /// Every integral solution with each variable within its `bounds`, by enumerating
+    /// the free variables over their bounds. Fails unless there is one bound per variable.
+    pub fn integer_solutions<'a>(
+        &'a self,
+        bounds: &'a [RangeInclusive<i64>],
+    ) -> Result<impl Iterator<Item = Vec<i64>> + 'a, Error> {
+        if bounds.len() != self.particular.len() {
+            return Err(Error::msg(format!(
+                "Expected {} bounds, one per variable, found {}.",
+                self.particular.len(),
+                bounds.len()
+            )));
+        }
+        Ok(self
+            .free_variables
+            .iter()
+            .map(|&free| bounds[free].clone())
+            .multi_cartesian_product()
+            .filter_map(|free_values| self.integer_solution_unchecked(&free_values))
+            .filter(move |x| {
+                x.iter()
+                    .zip(bounds)
+                    .all(|(value, bound)| bound.contains(value))
+            }))
+    }
+
+    /// Whether there is an integral solution with each variable within its `bounds`.
+    pub fn is_integer_feasible(&self, bounds: &[RangeInclusive<i64>]) -> Result<bool, Error> {
+        Ok(self.integer_solutions(bounds)?.next().is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_claw_machines() -> Result<(), Error> {
+        // 2024 day 13: presses of A and B reaching the prize.
+        let a: DMatrix<i64> = DMatrix::from_row_slice(2, 2, &[94, 22, 34, 67]);
+        let solutions: Solutions =
+            solve_exact(&a, &DVector::from_row_slice(&[8400, 5400]))?.unwrap();
+        assert_eq!(
+            solutions.unique(),
+            Some(&DVector::from_row_slice(&[rational(80), rational(40)]))
+        );
+        assert_eq!(solutions.integer_solution(&[])?, Some(vec![80, 40]));
+        assert!(solutions.integer_solution(&[1]).is_err());
+        assert_eq!(
+            solutions
+                .integer_solutions(&[0..=100, 0..=100])?
+                .collect::<Vec<_>>(),
+            vec![vec![80, 40]]
+        );
+
+        let a: DMatrix<i64> = DMatrix::from_row_slice(2, 2, &[26, 67, 66, 21]);
+        let solutions: Solutions =
+            solve_exact(&a, &DVector::from_row_slice(&[12748, 12176]))?.unwrap();
+        assert_eq!(solutions.integer_solution(&[])?, None);
+
+        let parallel: DMatrix<i64> = DMatrix::from_row_slice(2, 2, &[1, 2, 2, 4]);
+        assert_eq!(
+            solve_exact(&parallel, &DVector::from_row_slice(&[1, 3]))?,
+            None
+        );
+        assert!(solve_exact(&parallel, &DVector::from_row_slice(&[1, 3, 5])).is_err());
+        assert!(solutions.integer_solutions(&[0..=100]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_button_presses() -> Result<(), Error> {
+        // 2025 day 10: buttons (3) (1,3) (2) (2,3) (0,2) (0,1) reaching joltages {3,5,4,7}.
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let a: DMatrix<i64> = DMatrix::from_fn(4, 6, |counter, button| {
+            buttons[button].contains(&counter) as i64
+        });
+        let solutions: Solutions =
+            solve_exact(&a, &DVector::from_row_slice(&[3, 5, 4, 7]))?.unwrap();
+        assert_eq!(solutions.free_variables.len(), 2);
+        assert!(solutions.integer_solution(&[0]).is_err());
+        assert!(solutions.integer_solution(&[0, 0, 0]).is_err());
+        let bounds: Vec<RangeInclusive<i64>> = vec![0..=7; 6];
+        let fewest: Option<i64> = solutions
+            .integer_solutions(&bounds)?
+            .map(|presses| presses.iter().sum())
+            .min();
+        assert_eq!(fewest, Some(10));
+        assert!(!solutions.is_integer_feasible(&vec![0..=1; 6])?);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_finds_known_solution(
+            entries in prop::collection::vec(-3..=3i64, 12),
+            x in prop::collection::vec(-4..=4i64, 4),
+        ) {
+            let a: DMatrix<i64> = DMatrix::from_row_slice(3, 4, &entries);
+            let b: DVector<i64> = &a * DVector::from_row_slice(&x);
+            let solutions: Solutions = solve_exact(&a, &b).unwrap().unwrap();
+            let bounds: Vec<RangeInclusive<i64>> = vec![-4..=4; 4];
+            let found: Vec<Vec<i64>> = solutions.integer_solutions(&bounds).unwrap().collect();
+            prop_assert!(found.contains(&x));
+            for y in found {
+                prop_assert_eq!(&a * DVector::from_row_slice(&y), b.clone());
+            }
+        }
+    }
+}
@@ -1,47 +1,417 @@
 use anyhow::{Error, Result};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::engine::{ArgValueCompleter, CompletionCandidate};
+use clap_complete::env::{CompleteEnv, Shells};
+use std::ffi::OsStr;
 use std::path::PathBuf;
-use tracing::Level;
-use tracing_subscriber::FmtSubscriber;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
-use every_advent_of_code::calendar::{year_2015, year_2025};
+use every_advent_of_code::answers::Answers;
+use every_advent_of_code::calendar;
+use every_advent_of_code::check::InputProblem;
+use every_advent_of_code::config::{BenchConfig, Config, OutputFormat};
+use every_advent_of_code::progress::{render_calendar, render_markdown};
 
 /// Every Advent of Code in Rust.
 #[derive(clap::Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(name = "ra", version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Solve a puzzle.
+    Run(Args),
+    /// Print the completion script for a shell.
+    Completions {
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+    /// Print the man page.
+    Man,
+    /// Check the shape of an input without solving the puzzle.
+    Check {
+        #[arg(add = ArgValueCompleter::new(complete_year))]
+        year: usize,
+        #[arg(add = ArgValueCompleter::new(complete_day))]
+        day: usize,
+        input: PathBuf,
+    },
+    /// Print a random input, for stress testing and benchmarking.
+    Gen {
+        #[arg(add = ArgValueCompleter::new(complete_year))]
+        year: usize,
+        #[arg(add = ArgValueCompleter::new(complete_day))]
+        day: usize,
+        /// Size of the input, e.g., number of lines or map side.
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Show which puzzles are solved and verified.
+    Calendar {
+        /// Print a Markdown table, as used in the README.
+        #[arg(long, default_value_t = false)]
+        markdown: bool,
+        /// Configuration file. Defaults to the closest `.ra.toml` up from the current directory.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Path to the file with the verified answers.
+        #[arg(long)]
+        answers: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
-    /// Path to input link_assoc file. Supports CSV and CSV.GZ.
-    input: PathBuf,
+    #[arg(add = ArgValueCompleter::new(complete_year))]
     year: usize,
+    #[arg(add = ArgValueCompleter::new(complete_day))]
     day: usize,
     part: usize,
+    /// Path to the puzzle input. Supports GZ compression.
+    /// Defaults to `<inputs>/<year>/day<day>.txt` when an inputs root is configured.
+    input: Option<PathBuf>,
+    /// Named solver variant, e.g. `naive` or `fast`. Defaults to the regular solver.
+    #[arg(long)]
+    variant: Option<String>,
+
+    /// Configuration file. Defaults to the closest `.ra.toml` up from the current directory.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Root folder of the puzzle inputs.
+    #[arg(long)]
+    inputs: Option<PathBuf>,
+    #[arg(long, value_enum)]
+    output: Option<OutputFormat>,
+    /// Log filter, e.g. `debug` or `every_advent_of_code=trace`.
+    #[arg(long)]
+    log: Option<String>,
+    /// Solver timeout, in seconds, also applied to each benchmark iteration.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Path to the file with the verified answers.
+    #[arg(long)]
+    answers: Option<PathBuf>,
+
+    /// Run the solver repeatedly and report its timing.
+    #[arg(long, default_value_t = false)]
+    bench: bool,
+    #[arg(long)]
+    warmup: Option<usize>,
+    #[arg(long)]
+    iterations: Option<usize>,
 
+    /// Log at debug level, unless `--log` is given.
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
 }
 
-fn main() -> Result<(), Error> {
-    let args: Args = Args::parse();
-
-    // Setup logger
-    let subscriber: FmtSubscriber = FmtSubscriber::builder()
-        .with_max_level(if args.verbose {
-            Level::DEBUG
-        } else {
-            Level::INFO
+fn complete_year(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current: &str = current.to_str().unwrap_or_default();
+    calendar::years()
+        .iter()
+        .map(|year| year.to_string())
+        .filter(|year| year.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Year already typed on the command line being completed, if any: the first positional
+/// argument of the subcommand, as in `ra run <year> <day> <part>`.
+fn requested_year(args: &[String]) -> Option<usize> {
+    // Completion passes the words of the command line after `--`, from the binary name.
+    let words: &[String] = match args.iter().position(|arg| arg == "--") {
+        Some(index) => &args[index + 1..],
+        None => args,
+    };
+    let command: clap::Command = Cli::command();
+    let subcommand: &clap::Command = command.find_subcommand(words.get(1)?)?;
+    let mut rest = words[2..].iter();
+    while let Some(word) = rest.next() {
+        if let Some(long) = word.strip_prefix("--") {
+            // The value of an option is never the year, even when it is a number.
+            let takes_value: bool = !long.contains('=')
+                && subcommand
+                    .get_arguments()
+                    .any(|arg| arg.get_long() == Some(long) && arg.get_action().takes_values());
+            if takes_value {
+                rest.next();
+            }
+        } else if !word.starts_with('-') {
+            return word.parse().ok();
+        }
+    }
+    None
+}
+
+/// Complete days, restricted to the ones registered for the requested year.
+fn complete_day(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current: &str = current.to_str().unwrap_or_default();
+    let args: Vec<String> = std::env::args().collect();
+    let mut days: Vec<usize> = match requested_year(&args) {
+        Some(year) => calendar::days(year),
+        None => calendar::years()
+            .into_iter()
+            .flat_map(calendar::days)
+            .collect(),
+    };
+    days.sort();
+    days.dedup();
+    days.iter()
+        .map(|day| day.to_string())
+        .filter(|day| day.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+impl Args {
+    /// Settings passed on the command line, which override the configuration file.
+    fn overrides(&self) -> Config {
+        Config {
+            inputs: self.inputs.clone(),
+            output: self.output,
+            log: self
+                .log
+                .clone()
+                .or(self.verbose.then(|| "debug".to_string())),
+            timeout: self.timeout,
+            answers: self.answers.clone(),
+            bench: BenchConfig {
+                warmup: self.warmup,
+                iterations: self.iterations,
+            },
+        }
+    }
+}
+
+fn setup_logger(config: &Config) -> Result<(), Error> {
+    let builder =
+        FmtSubscriber::builder().with_env_filter(EnvFilter::try_new(config.log_filter())?);
+    match config.output.unwrap_or_default() {
+        OutputFormat::Full => tracing::subscriber::set_global_default(builder.finish()),
+        OutputFormat::Compact => {
+            tracing::subscriber::set_global_default(builder.compact().finish())
+        }
+        OutputFormat::Pretty => tracing::subscriber::set_global_default(builder.pretty().finish()),
+    }?;
+    Ok(())
+}
+
+/// Fail unless the day has a solver, before looking at its input.
+fn ensure_solved(year: usize, day: usize) -> Result<(), Error> {
+    if calendar::days(year).contains(&day) {
+        Ok(())
+    } else {
+        Err(Error::msg(format!("No solver for {} day {}.", year, day)))
+    }
+}
+
+/// Run a day in a separate thread, giving up after `timeout` if set.
+fn run_day_with_timeout(
+    year: usize,
+    day: usize,
+    part: usize,
+    variant: Option<String>,
+    input: PathBuf,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
+    let Some(timeout) = timeout else {
+        return calendar::run_day(year, day, part, variant.as_deref(), input);
+    };
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        sender.send(calendar::run_day(
+            year,
+            day,
+            part,
+            variant.as_deref(),
+            input,
+        ))
+    });
+    receiver
+        .recv_timeout(timeout)
+        .map_err(|_| Error::msg(format!("Timed out after {}s.", timeout.as_secs())))?
+}
+
+fn run(args: Args) -> Result<(), Error> {
+    // Precedence: command line, RUST_LOG, configuration file.
+    let config: Config = Config::resolve(args.config.as_deref(), &std::env::current_dir()?)?
+        .overridden_by(Config {
+            log: std::env::var("RUST_LOG").ok(),
+            ..Config::default()
         })
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+        .overridden_by(args.overrides());
+
+    setup_logger(&config)?;
+
+    let input: PathBuf = args
+        .input
+        .clone()
+        .or(config.input_path(args.year, args.day))
+        .ok_or(Error::msg("No input given and no inputs root configured."))?;
 
     tracing::info!("Running {} {} pt{}", args.year, args.day, args.part);
-    tracing::info!("Input from {}", args.input.to_str().unwrap());
+    tracing::info!("Input from {}", input.display());
 
-    match args.year {
-        2015 => year_2015::run_day(args.day, args.part, args.input),
-        2025 => year_2025::run_day(args.day, args.part, args.input),
-        _ => panic!("Unsupported year {}", args.year),
-    }?;
+    let timeout: Option<Duration> = config.timeout_duration();
+    if args.bench {
+        // The timeout applies to every iteration, not to the whole benchmark.
+        for _ in 0..config.warmup() {
+            run_day_with_timeout(
+                args.year,
+                args.day,
+                args.part,
+                args.variant.clone(),
+                input.clone(),
+                timeout,
+            )?;
+        }
+        let mut timings: Vec<Duration> = Vec::new();
+        for _ in 0..config.iterations() {
+            let start: Instant = Instant::now();
+            run_day_with_timeout(
+                args.year,
+                args.day,
+                args.part,
+                args.variant.clone(),
+                input.clone(),
+                timeout,
+            )?;
+            timings.push(start.elapsed());
+        }
+        let total: Duration = timings.iter().sum();
+        tracing::info!(
+            "{} iterations: min {:?}, mean {:?}, max {:?}",
+            timings.len(),
+            timings.iter().min().unwrap_or(&Duration::ZERO),
+            total / u32::try_from(timings.len().max(1))?,
+            timings.iter().max().unwrap_or(&Duration::ZERO),
+        );
+    } else {
+        run_day_with_timeout(
+            args.year,
+            args.day,
+            args.part,
+            args.variant.clone(),
+            input,
+            timeout,
+        )?;
+    }
 
     tracing::info!("Done.");
     Ok(())
 }
+
+fn main() -> Result<(), Error> {
+    CompleteEnv::with_factory(Cli::command).complete();
+
+    match Cli::parse().command {
+        Command::Run(args) => run(args),
+        Command::Completions { shell } => {
+            let name: &str = match shell {
+                CompletionShell::Bash => "bash",
+                CompletionShell::Zsh => "zsh",
+                CompletionShell::Fish => "fish",
+            };
+            Shells::builtins()
+                .completer(name)
+                .ok_or(Error::msg(format!("Unsupported shell {}", name)))?
+                .write_registration("COMPLETE", "ra", "ra", "ra", &mut std::io::stdout())?;
+            Ok(())
+        }
+        Command::Man => {
+            clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+            Ok(())
+        }
+        Command::Check { year, day, input } => {
+            ensure_solved(year, day)?;
+            let problems: Vec<InputProblem> = calendar::check_day(year, day, input.clone())?;
+            for problem in &problems {
+                println!("{}: {}", input.display(), problem);
+            }
+            if problems.is_empty() {
+                println!("{}: OK", input.display());
+                Ok(())
+            } else {
+                Err(Error::msg(format!(
+                    "Found {} problem(s) in the input of {} {}.",
+                    problems.len(),
+                    year,
+                    day
+                )))
+            }
+        }
+        Command::Gen {
+            year,
+            day,
+            size,
+            seed,
+        } => {
+            ensure_solved(year, day)?;
+            for line in calendar::generate_day(year, day, size, seed)? {
+                println!("{}", line);
+            }
+            Ok(())
+        }
+        Command::Calendar {
+            markdown,
+            config,
+            answers,
+        } => {
+            let config: Config = Config::resolve(config.as_deref(), &std::env::current_dir()?)?
+                .overridden_by(Config {
+                    answers,
+                    ..Config::default()
+                });
+            let answers: Answers = match &config.answers {
+                Some(path) if path.exists() => Answers::load(path)?,
+                _ => Answers::default(),
+            };
+            if markdown {
+                print!("{}", render_markdown(&answers));
+            } else {
+                print!("{}", render_calendar(&answers));
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn to_args(line: &str) -> Vec<String> {
+        line.split(' ').map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_requested_year() {
+        assert_eq!(requested_year(&to_args("ra -- ra run 2025 ")), Some(2025));
+        assert_eq!(
+            requested_year(&to_args("ra -- ra run --timeout 2015 2025 1")),
+            Some(2025)
+        );
+        assert_eq!(
+            requested_year(&to_args("ra -- ra gen --size=2015 -v 2019 ")),
+            Some(2019)
+        );
+        // A day or part is never taken for the year.
+        assert_eq!(requested_year(&to_args("ra -- ra check x 2015 ")), None);
+        assert_eq!(requested_year(&to_args("ra -- ra calendar")), None);
+    }
+}
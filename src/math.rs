@@ -0,0 +1,256 @@
+use anyhow::{Error, Result};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Least common multiple, or `None` when it overflows.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of all the values, 1 for none, or `None` when it overflows.
+pub fn lcm_all(values: &[u64]) -> Option<u64> {
+    values.iter().try_fold(1, |acc, &value| lcm(acc, value))
+}
+
+/// `(g, x, y)` such that `a * x + b * y = g`, with `g` the non-negative gcd of `a` and `b`,
+/// or `None` when a value overflows an `i128`, which only happens around `i128::MIN`.
+pub fn extended_gcd(a: i128, b: i128) -> Option<(i128, i128, i128)> {
+    let (mut old_r, mut r): (i128, i128) = (a, b);
+    let (mut old_x, mut x): (i128, i128) = (1, 0);
+    let (mut old_y, mut y): (i128, i128) = (0, 1);
+    let next =
+        |old: i128, current: i128, quotient: i128| old.checked_sub(quotient.checked_mul(current)?);
+    while r != 0 {
+        let quotient: i128 = old_r.checked_div(r)?;
+        let remainder: i128 = next(old_r, r, quotient)?;
+        if remainder == 0 {
+            // The next coefficients would be `b / g` and `a / g`, which may not fit.
+            (old_r, old_x, old_y) = (r, x, y);
+            break;
+        }
+        (old_r, r) = (r, remainder);
+        (old_x, x) = (x, next(old_x, x, quotient)?);
+        (old_y, y) = (y, next(old_y, y, quotient)?);
+    }
+    if old_r < 0 {
+        Some((
+            old_r.checked_neg()?,
+            old_x.checked_neg()?,
+            old_y.checked_neg()?,
+        ))
+    } else {
+        Some((old_r, old_x, old_y))
+    }
+}
+
+/// `x` in `0..modulus` such that `a * x = 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus)?;
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// `a * b mod modulus` for any 128-bit values, without overflow, or `None` for a zero
+/// modulus.
+pub fn mul_mod(a: u128, b: u128, modulus: u128) -> Option<u128> {
+    if modulus == 0 {
+        return None;
+    }
+    let (mut a, mut b): (u128, u128) = (a % modulus, b % modulus);
+    if let Some(product) = a.checked_mul(b) {
+        return Some(product % modulus);
+    }
+    // Double and add, keeping every intermediate value below `modulus`.
+    let mut result: u128 = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+    Some(result)
+}
+
+/// `a + b mod modulus`, for `a` and `b` already below `modulus`.
+fn add_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// `base^exponent mod modulus` for any 128-bit values, or `None` for a zero modulus.
+pub fn modpow(base: u128, mut exponent: u128, modulus: u128) -> Option<u128> {
+    if modulus == 0 {
+        return None;
+    }
+    let mut result: u128 = 1 % modulus;
+    let mut base: u128 = base % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus)?;
+        }
+        base = mul_mod(base, base, modulus)?;
+        exponent >>= 1;
+    }
+    Some(result)
+}
+
+/// Solve the system `x = residue (mod modulus)` for every `(residue, modulus)`, with
+/// moduli that need not be coprime. Returns `(x, lcm)` with `x` in `0..lcm`, or `None`
+/// when the congruences are incompatible or a modulus is not positive. Arbitrary
+/// precision, for huge moduli.
+pub fn crt_big(congruences: &[(BigInt, BigInt)]) -> Option<(BigInt, BigInt)> {
+    if congruences.iter().any(|(_, m)| !m.is_positive()) {
+        return None;
+    }
+    let mut x: BigInt = BigInt::zero();
+    let mut modulus: BigInt = BigInt::one();
+    for (residue, m) in congruences {
+        let gcd = modulus.extended_gcd(m);
+        let difference: BigInt = residue - &x;
+        if !(&difference % &gcd.gcd).is_zero() {
+            return None;
+        }
+        // `modulus * k = difference (mod m)`, divided through by the gcd.
+        let step: BigInt = m / &gcd.gcd;
+        let k: BigInt = (difference / &gcd.gcd * gcd.x).mod_floor(&step);
+        x += &modulus * k;
+        modulus *= step;
+        x = x.mod_floor(&modulus);
+    }
+    Some((x, modulus))
+}
+
+/// `crt_big` on 128-bit integers, failing when the combined modulus does not fit.
+pub fn crt(congruences: &[(i128, i128)]) -> Result<Option<(i128, i128)>, Error> {
+    if let Some(&(_, m)) = congruences.iter().find(|&&(_, m)| m <= 0) {
+        return Err(Error::msg(format!(
+            "Modulus must be positive, found {}.",
+            m
+        )));
+    }
+    let big: Vec<(BigInt, BigInt)> = congruences
+        .iter()
+        .map(|&(residue, m)| (BigInt::from(residue), BigInt::from(m)))
+        .collect();
+    let Some((x, modulus)) = crt_big(&big) else {
+        return Ok(None);
+    };
+    let overflow = |_| Error::msg(format!("CRT modulus {} overflows i128.", modulus));
+    Ok(Some((
+        i128::try_from(&x).map_err(overflow)?,
+        i128::try_from(&modulus).map_err(overflow)?,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm_all(&[2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all(&[]), Some(1));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(extended_gcd(240, 46), Some((2, -9, 47)));
+        assert_eq!(extended_gcd(-4, 6), Some((2, 1, 1)));
+        assert_eq!(extended_gcd(i128::MIN, 0), None);
+        assert_eq!(extended_gcd(i128::MIN, 1), Some((1, 0, 1)));
+        assert_eq!(extended_gcd(i128::MIN, -1), None);
+        let (g, x, y) = extended_gcd(i128::MIN, 3).unwrap();
+        assert_eq!(g, 1);
+        assert_eq!(
+            BigInt::from(i128::MIN) * x + BigInt::from(y) * 3,
+            BigInt::one()
+        );
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_modpow() {
+        // 2015 day 25: the code after 2 steps.
+        assert_eq!(
+            modpow(252533, 2, 33554393).map(|power| 20151125 * power % 33554393),
+            Some(18749137)
+        );
+        let large: u128 = (1 << 127) - 1;
+        assert_eq!(modpow(3, large - 1, large), Some(1));
+        assert_eq!(mul_mod(large - 1, large - 1, large), Some(1));
+        assert_eq!(modpow(5, 0, 1), Some(0));
+        assert_eq!(modpow(5, 2, 0), None);
+        assert_eq!(mul_mod(5, 2, 0), None);
+    }
+
+    #[test]
+    fn test_crt() -> Result<(), Error> {
+        // 2020 day 13 example: bus 17 at t, 13 at t + 2, 19 at t + 3.
+        assert_eq!(crt(&[(0, 17), (-2, 13), (-3, 19)])?, Some((3417, 4199)));
+        assert_eq!(crt(&[(2, 6), (4, 8)])?, Some((20, 24)));
+        assert_eq!(crt(&[(1, 6), (2, 8)])?, None);
+        assert_eq!(crt(&[])?, Some((0, 1)));
+        assert!(crt(&[(0, 0)]).is_err());
+        let primes: Vec<(i128, i128)> = [
+            1_000_000_007,
+            998_244_353,
+            1_000_000_009,
+            999_999_937,
+            2_147_483_647,
+        ]
+        .iter()
+        .map(|&p| (1, p))
+        .collect();
+        assert!(crt(&primes).is_err());
+        let big: Vec<(BigInt, BigInt)> = primes
+            .iter()
+            .map(|&(r, m)| (BigInt::from(r), BigInt::from(m)))
+            .collect();
+        let (x, modulus) = crt_big(&big).unwrap();
+        assert_eq!(x, BigInt::one());
+        assert!(modulus > BigInt::from(i128::MAX));
+        let zero: Vec<(BigInt, BigInt)> = vec![
+            (BigInt::one(), BigInt::from(3)),
+            (BigInt::zero(), BigInt::zero()),
+        ];
+        assert_eq!(crt_big(&zero), None);
+        assert_eq!(crt_big(&[(BigInt::one(), BigInt::from(-3))]), None);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_crt_matches_brute_force(
+            congruences in prop::collection::vec((0..50i128, 1..12i128), 0..4)
+        ) {
+            let lcm: i128 = congruences.iter().fold(1, |acc, &(_, m)| acc / gcd(acc as u64, m as u64) as i128 * m);
+            let expected: Option<i128> = (0..lcm).find(|x| {
+                congruences.iter().all(|&(r, m)| x.rem_euclid(m) == r.rem_euclid(m))
+            });
+            prop_assert_eq!(crt(&congruences).ok(), Some(expected.map(|x| (x, lcm))));
+        }
+
+        #[test]
+        fn test_modpow_matches_repeated_product(base in any::<u64>(), exponent in 0..64u128, modulus in 1..u64::MAX) {
+            let expected: u128 = (0..exponent).fold(1 % modulus as u128, |acc, _| acc * base as u128 % modulus as u128);
+            prop_assert_eq!(modpow(base as u128, exponent, modulus as u128), Some(expected));
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{Error, Result};
+use ndarray::{Array2, Axis, s};
+
+/// Glyphs of the 4x6 font, trimmed to their lit columns, rows separated by `\n`.
+const FONT_6: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// Glyphs of the 6x10 font, trimmed to their lit columns, rows separated by `\n`.
+const FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Render pixels with `#` for lit and `.` for dark, one line per row.
+pub fn render(pixels: &Array2<bool>) -> String {
+    pixels
+        .rows()
+        .into_iter()
+        .map(|row| {
+            row.iter()
+                .map(|&lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Read the letters drawn by the lit pixels, in the 4x6 or 6x10 font.
+/// Letters are split on dark columns, and blank borders are ignored.
+pub fn recognize(pixels: &Array2<bool>) -> Result<String, Error> {
+    let lit_rows: Vec<usize> = pixels
+        .axis_iter(Axis(0))
+        .enumerate()
+        .filter(|(_, row)| row.iter().any(|&lit| lit))
+        .map(|(index, _)| index)
+        .collect();
+    let (Some(&top), Some(&bottom)) = (lit_rows.first(), lit_rows.last()) else {
+        return Err(Error::msg("No lit pixels to read."));
+    };
+    let font: &[(char, &str)] = match bottom - top + 1 {
+        6 => FONT_6,
+        10 => FONT_10,
+        height => {
+            return Err(Error::msg(format!(
+                "Unsupported letter height {}, expected 6 or 10.",
+                height
+            )));
+        }
+    };
+
+    let lit_columns: Vec<bool> = pixels
+        .axis_iter(Axis(1))
+        .map(|column| column.iter().any(|&lit| lit))
+        .collect();
+    let mut text: String = String::new();
+    let mut col_index: usize = 0;
+    while col_index < lit_columns.len() {
+        if !lit_columns[col_index] {
+            col_index += 1;
+            continue;
+        }
+        let start: usize = col_index;
+        while col_index < lit_columns.len() && lit_columns[col_index] {
+            col_index += 1;
+        }
+        let glyph: String = render(&pixels.slice(s![top..=bottom, start..col_index]).to_owned());
+        let letter: char = font
+            .iter()
+            .find(|(_, shape)| *shape == glyph)
+            .map(|(letter, _)| *letter)
+            .ok_or(Error::msg(format!(
+                "Unknown glyph at column {}:\n{}",
+                start + 1,
+                glyph
+            )))?;
+        text.push(letter);
+    }
+    Ok(text)
+}
+
+/// Read the letters drawn in a string grid, where `#` and `█` are lit pixels.
+pub fn recognize_str(grid: &str) -> Result<String, Error> {
+    let lines: Vec<&str> = grid.lines().collect();
+    let width: usize = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut pixels: Array2<bool> = Array2::from_elem((lines.len(), width), false);
+    for (row_index, line) in lines.iter().enumerate() {
+        for (col_index, c) in line.chars().enumerate() {
+            pixels[(row_index, col_index)] = c == '#' || c == '█';
+        }
+    }
+    recognize(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_recognize_small_font() -> Result<(), Error> {
+        assert_eq!(
+            recognize_str(
+                "
+.##..###...##.
+#..#.#..#.#..#
+#..#.###..#...
+####.#..#.#...
+#..#.#..#.#..#
+#..#.###...##."
+            )?,
+            "ABC"
+        );
+        assert_eq!(
+            recognize_str(
+                "
+█  █ ███   ██
+█  █  █     █
+████  █     █
+█  █  █     █
+█  █  █  █  █
+█  █ ███  ██"
+            )?,
+            "HIJ"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_recognize_large_font() -> Result<(), Error> {
+        let h: Vec<&str> = FONT_10[6].1.lines().collect();
+        let l: Vec<&str> = FONT_10[9].1.lines().collect();
+        let grid: String = h
+            .iter()
+            .zip(l)
+            .map(|(h, l)| format!("..{}..{}", h, l))
+            .collect::<Vec<String>>()
+            .join("\n");
+        assert_eq!(recognize_str(&format!("........\n{}", grid))?, "HL");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let error: String = recognize_str("#..#\n#..#\n#..#\n#.##\n##.#\n#..#")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Unknown glyph at column 1:\n#..#\n#..#\n#..#\n#.##\n##.#\n#..#"
+        );
+        assert!(recognize_str("#\n#\n#").is_err());
+    }
+}
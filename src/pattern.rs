@@ -0,0 +1,281 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+
+/// Parse a line with a scanf-style pattern into a tuple of typed fields.
+///
+/// Fields are written `{name}`, or `{name:N}` to match exactly `N` characters, and
+/// every field is converted with `FromStr`. The pattern is compiled once per call site,
+/// like `let (x, y): (u32, u32) = parse_fmt!("{x},{y}", line)?;`. An invalid pattern
+/// is returned as an error, like an unmatched line.
+#[macro_export]
+macro_rules! parse_fmt {
+    ($pattern:literal, $line:expr) => {{
+        static PATTERN: std::sync::OnceLock<Result<$crate::pattern::Pattern, String>> =
+            std::sync::OnceLock::new();
+        match PATTERN.get_or_init(|| {
+            $crate::pattern::Pattern::new($pattern).map_err(|error| error.to_string())
+        }) {
+            Ok(pattern) => pattern.parse($line),
+            Err(error) => Err(anyhow::Error::msg(format!(
+                "Invalid pattern '{}': {}",
+                $pattern, error
+            ))),
+        }
+    }};
+}
+
+/// A piece of a pattern.
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field { name: String, width: Option<usize> },
+}
+
+/// A compiled line pattern, like `{x1},{y1} through {x2},{y2}`.
+/// Braces are escaped as `{{` and `}}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    segments: Vec<Segment>,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Pattern, Error> {
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut literal: String = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut field: String = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => field.push(c),
+                            None => {
+                                return Err(Error::msg(format!("Unclosed `{{` in '{}'.", pattern)));
+                            }
+                        }
+                    }
+                    let (name, width) = match field.split_once(':') {
+                        Some((name, width)) => (name, Some(width.parse::<usize>()?)),
+                        None => (field.as_str(), None),
+                    };
+                    if let Some(Segment::Field {
+                        name: previous,
+                        width: None,
+                    }) = segments.last()
+                        && literal.is_empty()
+                    {
+                        return Err(Error::msg(format!(
+                            "Field `{}` needs a width to be followed by field `{}`.",
+                            previous, name
+                        )));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field {
+                        name: name.to_string(),
+                        width,
+                    });
+                }
+                '}' => return Err(Error::msg(format!("Unmatched `}}` in '{}'.", pattern))),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Pattern { segments })
+    }
+
+    /// Split `line` into `(name, value)` pairs, one per field.
+    /// A field without width ends at the first occurrence of the literal after it.
+    pub fn captures<'a>(&'a self, line: &'a str) -> Result<Vec<(&'a str, &'a str)>, Error> {
+        let mut fields: Vec<(&str, &str)> = Vec::new();
+        let mut rest: &str = line;
+        for (index, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Literal(literal) => {
+                    let offset: usize = line.len() - rest.len();
+                    rest = rest.strip_prefix(literal.as_str()).ok_or_else(|| {
+                        let column: usize =
+                            line.char_indices().take_while(|&(i, _)| i < offset).count() + 1;
+                        Error::msg(format!(
+                            "Expected '{}' at column {} of '{}'.",
+                            literal, column, line
+                        ))
+                    })?;
+                }
+                Segment::Field {
+                    name,
+                    width: Some(width),
+                } => {
+                    let end: usize = rest
+                        .char_indices()
+                        .map(|(i, _)| i)
+                        .chain([rest.len()])
+                        .nth(*width)
+                        .ok_or(Error::msg(format!(
+                            "Field `{}` expects {} characters in '{}'.",
+                            name, width, line
+                        )))?;
+                    fields.push((name, &rest[..end]));
+                    rest = &rest[end..];
+                }
+                Segment::Field { name, width: None } => {
+                    let end: usize = match self.segments.get(index + 1) {
+                        Some(Segment::Literal(literal)) => {
+                            rest.find(literal.as_str()).ok_or(Error::msg(format!(
+                                "Expected '{}' after field `{}` in '{}'.",
+                                literal, name, line
+                            )))?
+                        }
+                        _ => rest.len(),
+                    };
+                    fields.push((name, &rest[..end]));
+                    rest = &rest[end..];
+                }
+            }
+        }
+        if !rest.is_empty() {
+            return Err(Error::msg(format!(
+                "Unexpected '{}' at the end of '{}'.",
+                rest, line
+            )));
+        }
+        Ok(fields)
+    }
+
+    /// Parse `line` into a tuple with one element per field.
+    pub fn parse<T: FromFields>(&self, line: &str) -> Result<T, Error> {
+        T::from_fields(&self.captures(line)?)
+            .map_err(|error| Error::msg(format!("{} in '{}'", error, line)))
+    }
+}
+
+/// Parse a single field value, naming the field on failure.
+fn parse_field<T>(name: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse::<T>()
+        .map_err(|error| Error::msg(format!("Invalid field `{}` ('{}'): {}", name, value, error)))
+}
+
+/// Tuples that can be built from the fields of a pattern.
+pub trait FromFields: Sized {
+    fn from_fields(fields: &[(&str, &str)]) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_fields {
+    ($count:literal, $($t:ident $value:ident),+) => {
+        impl<$($t),+> FromFields for ($($t,)+)
+        where
+            $($t: FromStr, $t::Err: Display),+
+        {
+            fn from_fields(fields: &[(&str, &str)]) -> Result<Self, Error> {
+                let [$($value),+] = fields else {
+                    return Err(Error::msg(format!(
+                        "Pattern has {} fields, expected {}",
+                        fields.len(),
+                        $count
+                    )));
+                };
+                Ok(($(parse_field::<$t>($value.0, $value.1)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_fields!(1, A a);
+impl_from_fields!(2, A a, B b);
+impl_from_fields!(3, A a, B b, C c);
+impl_from_fields!(4, A a, B b, C c, D d);
+impl_from_fields!(5, A a, B b, C c, D d, E e);
+impl_from_fields!(6, A a, B b, C c, D d, E e, F f);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse() -> Result<(), Error> {
+        let (op, x1, y1, x2, y2): (String, u32, u32, u32, u32) = parse_fmt!(
+            "turn {op} {x1},{y1} through {x2},{y2}",
+            "turn on 0,10 through 5,99"
+        )?;
+        assert_eq!((op.as_str(), x1, y1, x2, y2), ("on", 0, 10, 5, 99));
+        let (direction, distance): (char, i32) = parse_fmt!("{direction:1}{distance}", "L68")?;
+        assert_eq!((direction, distance), ('L', 68));
+        let (key,): (String,) = parse_fmt!("{{{key}}}", "{abc}")?;
+        assert_eq!(key, "abc");
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |result: Result<(u32, u32), Error>| result.unwrap_err().to_string();
+        assert_eq!(
+            error(parse_fmt!("{first}-{last}", "12-x4")),
+            "Invalid field `last` ('x4'): invalid digit found in string in '12-x4'"
+        );
+        assert_eq!(
+            error(parse_fmt!("{first}-{last}", "1234")),
+            "Expected '-' after field `first` in '1234'."
+        );
+        assert_eq!(
+            error(parse_fmt!("<{first}-{last}>", "<1-2>>")),
+            "Unexpected '>' at the end of '<1-2>>'."
+        );
+        assert_eq!(
+            error(parse_fmt!("<{first}-{last}>", "(1-2)")),
+            "Expected '<' at column 1 of '(1-2)'."
+        );
+        assert_eq!(
+            parse_fmt!("{first:2}<{last}>", "éé(1)")
+                .map(|t: (String, u32)| t)
+                .unwrap_err()
+                .to_string(),
+            "Expected '<' at column 3 of 'éé(1)'."
+        );
+        assert_eq!(
+            error(parse_fmt!("{first}-{last}-{extra}", "1-2-3")),
+            "Pattern has 3 fields, expected 2 in '1-2-3'"
+        );
+        assert_eq!(
+            error(parse_fmt!("{first}{last}", "12")),
+            "Invalid pattern '{first}{last}': Field `first` needs a width to be followed by field `last`."
+        );
+        assert!(Pattern::new("{a}{b}").is_err());
+        assert!(Pattern::new("{a:1}{b}").is_ok());
+        assert!(Pattern::new("a}").is_err());
+        assert!(Pattern::new("{a").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(line in "\\PC*") {
+            let _ = parse_fmt!("{a:2}x{b}x{c}", &line).map(|t: (String, u8, i8)| t);
+        }
+
+        #[test]
+        fn test_new_never_panics(pattern in "\\PC*") {
+            let _ = Pattern::new(&pattern);
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use anyhow::{Error, Result};
+
+use crate::grid::Position;
+
+/// A point on a plane, with `y` growing downwards like grid rows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A point in space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Point2 {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: Point2) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: Point2) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The 4 orthogonal neighbours, clockwise from north.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point2> {
+        Direction::CARDINALS.iter().map(move |d| self + d.offset())
+    }
+
+    /// The 8 orthogonal and diagonal neighbours, clockwise from north.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point2> {
+        Direction::ALL.iter().map(move |d| self + d.offset())
+    }
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: Point3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(&self, other: Point3) -> u64 {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    /// The 6 neighbours sharing a face.
+    pub fn neighbours6(self) -> impl Iterator<Item = Point3> {
+        [
+            Point3::new(-1, 0, 0),
+            Point3::new(1, 0, 0),
+            Point3::new(0, -1, 0),
+            Point3::new(0, 1, 0),
+            Point3::new(0, 0, -1),
+            Point3::new(0, 0, 1),
+        ]
+        .into_iter()
+        .map(move |offset| self + offset)
+    }
+
+    /// The 26 neighbours sharing a face, an edge or a corner.
+    pub fn neighbours26(self) -> impl Iterator<Item = Point3> {
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| Point3::new(x, y, z))))
+            .filter(|&offset| offset != Point3::ORIGIN)
+            .map(move |offset| self + offset)
+    }
+}
+
+macro_rules! impl_point_ops {
+    ($point:ident, $($field:ident),+) => {
+        impl Add for $point {
+            type Output = $point;
+
+            fn add(self, other: $point) -> $point {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = $point;
+
+            fn sub(self, other: $point) -> $point {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: $point) {
+                $(self.$field += other.$field;)+
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: $point) {
+                $(self.$field -= other.$field;)+
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = $point;
+
+            fn mul(self, factor: i64) -> $point {
+                $point { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = $point;
+
+            fn neg(self) -> $point {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2, x, y);
+impl_point_ops!(Point3, x, y, z);
+
+impl TryFrom<Position> for Point2 {
+    type Error = Error;
+
+    /// Convert a grid `(row, col)` position.
+    fn try_from(pos: Position) -> Result<Point2, Error> {
+        Ok(Point2::new(i64::try_from(pos.1)?, i64::try_from(pos.0)?))
+    }
+}
+
+impl TryFrom<Point2> for Position {
+    type Error = Error;
+
+    /// Convert to a grid `(row, col)` position.
+    fn try_from(point: Point2) -> Result<Position, Error> {
+        Ok((usize::try_from(point.y)?, usize::try_from(point.x)?))
+    }
+}
+
+/// A direction on the plane, with north towards negative `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// A turn, as in the `LR` notation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Turn {
+    /// Parse `L` or `R`.
+    pub fn parse(c: char) -> Result<Turn, Error> {
+        match c {
+            'L' => Ok(Turn::Left),
+            'R' => Ok(Turn::Right),
+            _ => Err(Error::msg(format!("Unknown turn '{}'.", c))),
+        }
+    }
+}
+
+impl Direction {
+    /// The 4 orthogonal directions, clockwise from north.
+    pub const CARDINALS: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The 8 directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// Rotate clockwise by `eighths` of a full turn, counter-clockwise when negative.
+    pub fn rotate(self, eighths: i32) -> Direction {
+        // Variants are declared in the order of `ALL`.
+        Direction::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// Rotate by 90 degrees counter-clockwise.
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    /// Rotate by 90 degrees clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn turn(self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Unit step in this direction.
+    pub fn offset(self) -> Point2 {
+        match self {
+            Direction::North => Point2::new(0, -1),
+            Direction::NorthEast => Point2::new(1, -1),
+            Direction::East => Point2::new(1, 0),
+            Direction::SouthEast => Point2::new(1, 1),
+            Direction::South => Point2::new(0, 1),
+            Direction::SouthWest => Point2::new(-1, 1),
+            Direction::West => Point2::new(-1, 0),
+            Direction::NorthWest => Point2::new(-1, -1),
+        }
+    }
+
+    fn parse_with(c: char, symbols: &str) -> Result<Direction, Error> {
+        symbols
+            .chars()
+            .position(|s| s == c)
+            .map(|index| Direction::CARDINALS[index])
+            .ok_or(Error::msg(format!(
+                "Unknown direction '{}', expected one of `{}`.",
+                c, symbols
+            )))
+    }
+
+    /// Parse an arrow among `^>v<`.
+    pub fn from_arrow(c: char) -> Result<Direction, Error> {
+        Direction::parse_with(c, "^>v<")
+    }
+
+    /// Parse a letter among `UDLR`.
+    pub fn from_udlr(c: char) -> Result<Direction, Error> {
+        Direction::parse_with(c, "URDL")
+    }
+
+    /// Parse a compass letter among `NESW`.
+    pub fn from_compass(c: char) -> Result<Direction, Error> {
+        Direction::parse_with(c, "NESW")
+    }
+}
+
+impl Add<Direction> for Point2 {
+    type Output = Point2;
+
+    fn add(self, direction: Direction) -> Point2 {
+        self + direction.offset()
+    }
+}
+
+impl AddAssign<Direction> for Point2 {
+    fn add_assign(&mut self, direction: Direction) {
+        *self += direction.offset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_point_ops() -> Result<(), Error> {
+        let a: Point2 = Point2::new(1, -2);
+        let b: Point2 = Point2::new(4, 2);
+        assert_eq!(a + b, Point2::new(5, 0));
+        assert_eq!(b - a, Point2::new(3, 4));
+        assert_eq!(-a * 3, Point2::new(-3, 6));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Point2::ORIGIN.neighbours8().count(), 8);
+        assert_eq!(
+            Point2::ORIGIN.neighbours4().collect::<Vec<Point2>>(),
+            [
+                Point2::new(0, -1),
+                Point2::new(1, 0),
+                Point2::new(0, 1),
+                Point2::new(-1, 0)
+            ]
+        );
+        assert_eq!(Point2::try_from((2, 5))?, Point2::new(5, 2));
+        assert_eq!(Position::try_from(Point2::new(5, 2))?, (2, 5));
+        assert!(Position::try_from(Point2::new(-1, 2)).is_err());
+
+        let c: Point3 = Point3::new(1, 2, 3);
+        assert_eq!(c.manhattan(Point3::ORIGIN), 6);
+        assert_eq!(c.chebyshev(Point3::ORIGIN), 3);
+        assert_eq!(c.neighbours6().count(), 6);
+        assert_eq!(c.neighbours26().count(), 26);
+        Ok(())
+    }
+
+    #[test]
+    fn test_direction() -> Result<(), Error> {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::NorthEast.turn_left(), Direction::NorthWest);
+        assert_eq!(Direction::SouthWest.opposite(), Direction::NorthEast);
+        assert_eq!(Direction::West.turn(Turn::parse('R')?), Direction::North);
+        assert_eq!(Direction::from_arrow('v')?, Direction::South);
+        assert_eq!(Direction::from_udlr('L')?, Direction::West);
+        assert_eq!(Direction::from_compass('E')?, Direction::East);
+        assert!(Direction::from_arrow('x').is_err());
+        assert!(Turn::parse('U').is_err());
+        let mut position: Point2 = Point2::ORIGIN;
+        for c in "^>>v".chars() {
+            position += Direction::from_arrow(c)?;
+        }
+        assert_eq!(position, Point2::new(2, 0));
+        Ok(())
+    }
+}
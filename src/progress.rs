@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use crate::answers::Answers;
+use crate::calendar;
+use crate::catalog::{self, Puzzle};
+
+/// Progress on a single puzzle part.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PartStatus {
+    Unsolved,
+    Solved,
+    Verified,
+}
+
+impl PartStatus {
+    fn symbol(&self) -> char {
+        match self {
+            PartStatus::Unsolved => '.',
+            PartStatus::Solved => '+',
+            PartStatus::Verified => '*',
+        }
+    }
+
+    fn markdown_symbol(&self) -> &'static str {
+        match self {
+            PartStatus::Unsolved => "",
+            PartStatus::Solved => "☆",
+            PartStatus::Verified => "★",
+        }
+    }
+}
+
+/// Status of a part: verified when the answers file has an entry for a solved part.
+pub fn part_status(answers: &Answers, year: usize, day: usize, part: usize) -> PartStatus {
+    if !calendar::days(year).contains(&day) {
+        PartStatus::Unsolved
+    } else if answers.is_verified(year, day, part) {
+        PartStatus::Verified
+    } else {
+        PartStatus::Solved
+    }
+}
+
+fn puzzle_statuses(answers: &Answers, puzzle: &Puzzle) -> Vec<PartStatus> {
+    (1..=puzzle.parts)
+        .map(|part| part_status(answers, puzzle.year, puzzle.day, part))
+        .collect()
+}
+
+fn count_stars(answers: &Answers, year: usize) -> (usize, usize) {
+    catalog::puzzles(year).fold((0, 0), |(stars, total), puzzle| {
+        let statuses: Vec<PartStatus> = puzzle_statuses(answers, &puzzle);
+        (
+            stars
+                + statuses
+                    .iter()
+                    .filter(|&&s| s == PartStatus::Verified)
+                    .count(),
+            total + statuses.len(),
+        )
+    })
+}
+
+/// Render the progress of every year as a star grid.
+pub fn render_calendar(answers: &Answers) -> String {
+    let mut out: String = String::from("    ");
+    for day in 1..=25 {
+        write!(out, " {:>2}", day).unwrap();
+    }
+    out.push('\n');
+    for year in catalog::years() {
+        write!(out, "{}", year).unwrap();
+        let mut width: usize = 0;
+        for puzzle in catalog::puzzles(year) {
+            let cell: String = puzzle_statuses(answers, &puzzle)
+                .iter()
+                .map(|s| s.symbol())
+                .collect();
+            write!(out, " {:>2}", cell).unwrap();
+            width += 3;
+        }
+        let (stars, total) = count_stars(answers, year);
+        writeln!(out, "{:>w$}  {:>2}/{}", "", stars, total, w = 75 - width).unwrap();
+    }
+    out.push_str("* verified answer, + solved, . unsolved\n");
+    out
+}
+
+/// Render the progress of every year as a Markdown table.
+pub fn render_markdown(answers: &Answers) -> String {
+    let mut out: String = String::from("| Year | Stars |");
+    for day in 1..=25 {
+        write!(out, " {} |", day).unwrap();
+    }
+    out.push_str("\n| ---: | ----: |");
+    out.push_str(&" :-: |".repeat(25));
+    out.push('\n');
+    for year in catalog::years() {
+        let (stars, total) = count_stars(answers, year);
+        write!(out, "| {} | {}/{} |", year, stars, total).unwrap();
+        let mut days: usize = 0;
+        for puzzle in catalog::puzzles(year) {
+            let cell: String = puzzle_statuses(answers, &puzzle)
+                .iter()
+                .map(|s| s.markdown_symbol())
+                .collect();
+            write!(out, " {} |", cell).unwrap();
+            days += 1;
+        }
+        out.push_str(&"  |".repeat(25 - days));
+        out.push('\n');
+    }
+    out.push_str("\n★ verified answer, ☆ solved\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Error, Result};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_part_status() -> Result<(), Error> {
+        let answers: Answers = Answers::parse("[2015.1]\n1 = \"280\"\n\n[2016.1]\n1 = \"1\"\n")?;
+        assert_eq!(part_status(&answers, 2015, 1, 1), PartStatus::Verified);
+        assert_eq!(part_status(&answers, 2015, 1, 2), PartStatus::Solved);
+        assert_eq!(part_status(&answers, 2016, 1, 1), PartStatus::Unsolved);
+        assert_eq!(count_stars(&answers, 2015), (1, 49));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_calendar() -> Result<(), Error> {
+        let answers: Answers = Answers::parse("[2015.1]\n1 = \"280\"\n2 = \"1797\"\n")?;
+        let calendar: String = render_calendar(&answers);
+        let row_2015: &str = calendar.lines().nth(1).unwrap_or_default();
+        assert!(row_2015.starts_with("2015 ** ++ ++ ++ ++ ++ .. .."));
+        assert!(row_2015.ends_with("..  .   2/49"));
+        let widths: Vec<usize> = calendar.lines().skip(1).take(11).map(|l| l.len()).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown: String = render_markdown(&Answers::default());
+        let rows: Vec<&str> = markdown.lines().collect();
+        assert_eq!(
+            rows[2],
+            format!("| 2015 | 0/49 |{}{}", " ☆☆ |".repeat(6), "  |".repeat(19))
+        );
+        assert!(
+            rows.iter()
+                .take(13)
+                .all(|row| row.matches('|').count() == 28)
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+/// Integer types usable as range endpoints.
+pub trait Endpoint: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// The next value, if any.
+    fn succ(self) -> Option<Self>;
+
+    /// The previous value, if any.
+    fn pred(self) -> Option<Self>;
+
+    /// Number of values in `start..=end`, with `start <= end`.
+    fn span(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_endpoint {
+    ($($t:ty),+) => {
+        $(
+            impl Endpoint for $t {
+                const MIN: $t = <$t>::MIN;
+                const MAX: $t = <$t>::MAX;
+
+                fn succ(self) -> Option<$t> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<$t> {
+                    self.checked_sub(1)
+                }
+
+                fn span(start: $t, end: $t) -> u128 {
+                    (end as i128 - start as i128) as u128 + 1
+                }
+            }
+        )+
+    };
+}
+
+impl_endpoint!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+/// Inclusive endpoints of a range, or `None` when it is empty.
+fn to_inclusive<T: Endpoint>(range: &impl RangeBounds<T>) -> Option<(T, T)> {
+    let start: T = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.succ()?,
+        Bound::Unbounded => T::MIN,
+    };
+    let end: T = match range.end_bound() {
+        Bound::Included(&end) => end,
+        Bound::Excluded(&end) => end.pred()?,
+        Bound::Unbounded => T::MAX,
+    };
+    (start <= end).then_some((start, end))
+}
+
+impl<T: Endpoint> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet { ranges: Vec::new() }
+    }
+
+    /// Add a range, inclusive (`a..=b`), half-open (`a..b`) or unbounded (`a..`).
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = to_inclusive(&range) else {
+            return;
+        };
+        // Ranges ending before `start - 1` and starting after `end + 1` are untouched.
+        let first: usize = self
+            .ranges
+            .partition_point(|&(_, e)| e.succ().is_some_and(|next| next < start));
+        let last: usize = self
+            .ranges
+            .partition_point(|&(s, _)| end.succ().is_none_or(|next| s <= next));
+        let merged: (T, T) = if first < last {
+            (
+                start.min(self.ranges[first].0),
+                end.max(self.ranges[last - 1].1),
+            )
+        } else {
+            (start, end)
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Whether `value` is in one of the ranges, in O(log n).
+    pub fn contains(&self, value: T) -> bool {
+        let index: usize = self.ranges.partition_point(|&(start, _)| start <= value);
+        index > 0 && value <= self.ranges[index - 1].1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Total number of values covered by the ranges.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| T::span(start, end))
+            .sum()
+    }
+
+    /// The normalized ranges, in increasing order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut union: RangeSet<T> = self.clone();
+        for range in other.ranges() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut ranges: Vec<(T, T)> = Vec::new();
+        let (mut i, mut j): (usize, usize) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b): ((T, T), (T, T)) = (self.ranges[i], other.ranges[j]);
+            let (start, end): (T, T) = (a.0.max(b.0), a.1.min(b.1));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// All the values of `T` not in the set.
+    pub fn complement(&self) -> RangeSet<T> {
+        let mut ranges: Vec<(T, T)> = Vec::new();
+        let mut next: Option<T> = Some(T::MIN);
+        for &(start, end) in &self.ranges {
+            if let Some(gap_start) = next
+                && let Some(gap_end) = start.pred()
+                && gap_start <= gap_end
+            {
+                ranges.push((gap_start, gap_end));
+            }
+            next = end.succ();
+        }
+        if let Some(gap_start) = next {
+            ranges.push((gap_start, T::MAX));
+        }
+        RangeSet { ranges }
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        self.intersection(&other.complement())
+    }
+}
+
+impl<T: Endpoint, R: RangeBounds<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> RangeSet<T> {
+        let mut set: RangeSet<T> = RangeSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_insert() {
+        let set: RangeSet<u32> = [3..=5, 10..=14, 16..=20, 12..=18].into_iter().collect();
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![3..=5, 10..=20]);
+        assert_eq!(set.len(), 14);
+        assert!(set.contains(5) && set.contains(17));
+        assert!(!set.contains(8) && !set.contains(21));
+
+        let mut half_open: RangeSet<i32> = RangeSet::new();
+        half_open.insert(0..3);
+        half_open.insert(3..5);
+        half_open.insert(7..7);
+        assert_eq!(half_open.ranges().collect::<Vec<_>>(), vec![0..=4]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet<u8> = [0..=10, 20..=30].into_iter().collect();
+        let b: RangeSet<u8> = [5..=25].into_iter().collect();
+        assert_eq!(a.union(&b).ranges().collect::<Vec<_>>(), vec![0..=30]);
+        assert_eq!(
+            a.intersection(&b).ranges().collect::<Vec<_>>(),
+            vec![5..=10, 20..=25]
+        );
+        assert_eq!(
+            a.difference(&b).ranges().collect::<Vec<_>>(),
+            vec![0..=4, 26..=30]
+        );
+        assert_eq!(
+            a.complement().ranges().collect::<Vec<_>>(),
+            vec![11..=19, 31..=255]
+        );
+        let full: RangeSet<u8> = [..].into_iter().collect::<RangeSet<u8>>();
+        assert_eq!(full.len(), 256);
+        assert!(full.complement().is_empty());
+    }
+
+    fn to_set(ranges: &[(u8, u8)]) -> (RangeSet<u8>, BTreeSet<u8>) {
+        let set: RangeSet<u8> = ranges.iter().map(|&(a, b)| a..=b).collect();
+        let model: BTreeSet<u8> = ranges.iter().flat_map(|&(a, b)| a..=b).collect();
+        (set, model)
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_model(
+            a in prop::collection::vec((any::<u8>(), any::<u8>()), 0..6),
+            b in prop::collection::vec((any::<u8>(), any::<u8>()), 0..6),
+        ) {
+            let (set_a, model_a) = to_set(&a);
+            let (set_b, model_b) = to_set(&b);
+            let values = |set: RangeSet<u8>| set.ranges().flatten().collect::<BTreeSet<u8>>();
+            prop_assert_eq!(set_a.len(), model_a.len() as u128);
+            prop_assert_eq!(values(set_a.union(&set_b)), &model_a | &model_b);
+            prop_assert_eq!(values(set_a.intersection(&set_b)), &model_a & &model_b);
+            prop_assert_eq!(values(set_a.difference(&set_b)), &model_a - &model_b);
+            for value in 0..=u8::MAX {
+                prop_assert_eq!(set_a.contains(value), model_a.contains(&value));
+            }
+            let ranges: Vec<RangeInclusive<u8>> = set_a.ranges().collect();
+            prop_assert!(ranges.windows(2).all(|w| *w[0].end() as u16 + 1 < *w[1].start() as u16));
+        }
+    }
+}
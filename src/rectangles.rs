@@ -0,0 +1,147 @@
+use anyhow::{Error, Result};
+use ndarray::s;
+
+use crate::grid::Grid;
+
+/// An axis-aligned rectangle of cells, with inclusive bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rectangle {
+    pub xmin: usize,
+    pub xmax: usize,
+    pub ymin: usize,
+    pub ymax: usize,
+}
+
+/// Sorted boundaries of the compressed cells along one axis: every `min` and `max + 1`.
+fn compress_axis(bounds: impl Iterator<Item = (usize, usize)>) -> Result<Vec<usize>, Error> {
+    let mut boundaries: Vec<usize> = Vec::new();
+    for (min, max) in bounds {
+        boundaries.push(min);
+        boundaries.push(
+            max.checked_add(1)
+                .ok_or(Error::msg(format!("Coordinate too large: {}", max)))?,
+        );
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    Ok(boundaries)
+}
+
+/// Apply every operation, in order, to the cells of its rectangle, all starting at
+/// `initial`, and sum `weight` of every cell.
+///
+/// Cells are compressed into rectangles that no operation edge crosses, and weighted by
+/// their area, so only the number of operations matters, not the range of the
+/// coordinates. Cells outside every rectangle are not counted: `weight(initial)` should
+/// be 0.
+pub fn apply_compressed<T: Copy, Op>(
+    operations: &[(Rectangle, Op)],
+    initial: T,
+    apply: impl Fn(T, &Op) -> T,
+    weight: impl Fn(T) -> u128,
+) -> Result<u128, Error> {
+    if let Some((rectangle, _)) = operations
+        .iter()
+        .find(|(r, _)| r.xmin > r.xmax || r.ymin > r.ymax)
+    {
+        return Err(Error::msg(format!("Corners out of order: {:?}", rectangle)));
+    }
+    let xs: Vec<usize> = compress_axis(operations.iter().map(|(r, _)| (r.xmin, r.xmax)))?;
+    let ys: Vec<usize> = compress_axis(operations.iter().map(|(r, _)| (r.ymin, r.ymax)))?;
+    if xs.is_empty() {
+        return Ok(0);
+    }
+
+    let mut cells: Grid<T> = Grid::from_elem(xs.len() - 1, ys.len() - 1, initial);
+    // Boundaries are exact, so the binary searches always hit.
+    let index = |axis: &[usize], value: usize| axis.partition_point(|&b| b < value);
+    for (rectangle, op) in operations {
+        cells
+            .view_mut()
+            .slice_mut(s![
+                index(&xs, rectangle.xmin)..index(&xs, rectangle.xmax + 1),
+                index(&ys, rectangle.ymin)..index(&ys, rectangle.ymax + 1)
+            ])
+            .mapv_inplace(|v| apply(v, op));
+    }
+
+    cells
+        .indexed_iter()
+        .try_fold(0u128, |total, ((x, y), &v)| {
+            let area: u128 = (xs[x + 1] - xs[x]) as u128 * (ys[y + 1] - ys[y]) as u128;
+            weight(v)
+                .checked_mul(area)
+                .and_then(|cell| total.checked_add(cell))
+        })
+        .ok_or(Error::msg("Total weight overflows."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn rectangle(xmin: usize, ymin: usize, xmax: usize, ymax: usize) -> Rectangle {
+        Rectangle {
+            xmin,
+            xmax,
+            ymin,
+            ymax,
+        }
+    }
+
+    fn count_lit(operations: &[(Rectangle, bool)]) -> Result<u128, Error> {
+        apply_compressed(operations, false, |_, &on| on, u128::from)
+    }
+
+    #[test]
+    fn test_apply_compressed() -> Result<(), Error> {
+        assert_eq!(count_lit(&[])?, 0);
+        assert_eq!(
+            count_lit(&[
+                (rectangle(0, 0, 9, 9), true),
+                (rectangle(5, 5, 14, 14), true),
+                (rectangle(0, 0, 4, 4), false),
+            ])?,
+            100 + 100 - 25 - 25
+        );
+        // Far larger than any dense grid.
+        assert_eq!(
+            count_lit(&[(rectangle(0, 0, 999_999_999, 999_999_999), true)])?,
+            1_000_000_000 * 1_000_000_000
+        );
+        let brightness: u128 = apply_compressed(
+            &[(rectangle(0, 0, 1, 1), 3u64), (rectangle(1, 1, 2, 2), 1)],
+            0u64,
+            |v, &delta| v + delta,
+            u128::from,
+        )?;
+        assert_eq!(brightness, 4 * 3 + 4);
+        assert!(count_lit(&[(rectangle(0, 0, usize::MAX, 0), true)]).is_err());
+        assert!(count_lit(&[(rectangle(5, 0, 4, 0), true)]).is_err());
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_dense_grid(
+            operations in prop::collection::vec((0..12usize, 0..12usize, 0..12usize, 0..12usize, any::<bool>()), 0..8)
+        ) {
+            let operations: Vec<(Rectangle, bool)> = operations
+                .into_iter()
+                .map(|(x1, y1, x2, y2, on)| (rectangle(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)), on))
+                .collect();
+            let mut dense: Grid<bool> = Grid::from_elem(12, 12, false);
+            for (r, on) in &operations {
+                for x in r.xmin..=r.xmax {
+                    for y in r.ymin..=r.ymax {
+                        dense[(x, y)] = *on;
+                    }
+                }
+            }
+            let expected: u128 = dense.iter().filter(|&&lit| lit).count() as u128;
+            prop_assert_eq!(count_lit(&operations).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,490 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use petgraph::graph::{Graph, IndexType, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::grid::{Grid, Position};
+
+/// A path from the start to a goal, both included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+/// Counters of a search run.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    /// States taken out of the frontier and expanded.
+    pub visited: usize,
+    /// States discovered, including the start.
+    pub discovered: usize,
+}
+
+/// Outcome of a search: the path found, if any, and the work it took.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult<S, C> {
+    pub path: Option<Path<S, C>>,
+    pub stats: SearchStats,
+}
+
+/// Discovered states, each with the index of the state it was reached from.
+struct Discovered<S> {
+    states: Vec<S>,
+    parents: Vec<usize>,
+    indices: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Discovered<S> {
+    fn new(start: S) -> Discovered<S> {
+        Discovered {
+            states: vec![start.clone()],
+            parents: vec![0],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Index of `state`, and whether it was just discovered.
+    fn insert(&mut self, state: S, parent: usize) -> (usize, bool) {
+        match self.indices.entry(state) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let index: usize = self.states.len();
+                self.states.push(entry.key().clone());
+                self.parents.push(parent);
+                entry.insert(index);
+                (index, true)
+            }
+        }
+    }
+
+    /// States from the start to `index`.
+    fn path_to(&self, mut index: usize) -> Vec<S> {
+        let mut path: Vec<S> = vec![self.states[index].clone()];
+        while index != 0 {
+            index = self.parents[index];
+            path.push(self.states[index].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search for the fewest steps from `start` to a goal state.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut discovered: Discovered<S> = Discovered::new(start);
+    let mut queue: VecDeque<(usize, usize)> = VecDeque::from([(0, 0)]);
+    let mut stats: SearchStats = SearchStats {
+        visited: 0,
+        discovered: 1,
+    };
+    while let Some((index, steps)) = queue.pop_front() {
+        stats.visited += 1;
+        if is_goal(&discovered.states[index]) {
+            return SearchResult {
+                path: Some(Path {
+                    states: discovered.path_to(index),
+                    cost: steps,
+                }),
+                stats,
+            };
+        }
+        for next in successors(&discovered.states[index]) {
+            let (next_index, is_new) = discovered.insert(next, index);
+            if is_new {
+                stats.discovered += 1;
+                queue.push_back((next_index, steps + 1));
+            }
+        }
+    }
+    SearchResult { path: None, stats }
+}
+
+/// Fewest steps from `start` to every reachable state.
+pub fn bfs_distances<S, I>(start: S, mut successors: impl FnMut(&S) -> I) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut queue: VecDeque<(S, usize)> = VecDeque::from([(start, 0)]);
+    while let Some((state, steps)) = queue.pop_front() {
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(steps + 1);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Dijkstra's search for the cheapest path from `start` to a goal state.
+/// Costs must not be negative, and `C::default()` is the zero cost.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search for the cheapest path from `start` to a goal state.
+/// For the path to be optimal, `heuristic` must be consistent: never more than
+/// the cost of a step plus the estimate after it, and zero at the goal.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut discovered: Discovered<S> = Discovered::new(start.clone());
+    let mut costs: Vec<C> = vec![C::default()];
+    let mut closed: Vec<bool> = vec![false];
+    // Ordered by estimated total cost, then by cost so far.
+    let mut heap: BinaryHeap<Reverse<(C, C, usize)>> =
+        BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut stats: SearchStats = SearchStats {
+        visited: 0,
+        discovered: 1,
+    };
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        if closed[index] || cost > costs[index] {
+            continue;
+        }
+        closed[index] = true;
+        stats.visited += 1;
+        if is_goal(&discovered.states[index]) {
+            return SearchResult {
+                path: Some(Path {
+                    states: discovered.path_to(index),
+                    cost,
+                }),
+                stats,
+            };
+        }
+        for (next, step_cost) in successors(&discovered.states[index]) {
+            let next_cost: C = cost + step_cost;
+            let (next_index, is_new) = discovered.insert(next, index);
+            if is_new {
+                stats.discovered += 1;
+                costs.push(next_cost);
+                closed.push(false);
+            } else if closed[next_index] || next_cost >= costs[next_index] {
+                continue;
+            } else {
+                costs[next_index] = next_cost;
+                discovered.parents[next_index] = index;
+            }
+            let estimate: C = next_cost + heuristic(&discovered.states[next_index]);
+            heap.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+    SearchResult { path: None, stats }
+}
+
+/// Breadth-first search from both ends, for the fewest steps from `start` to `goal`.
+/// `predecessors` lists the states with an edge to a given state, which is
+/// `successors` again on undirected graphs.
+pub fn bidirectional_bfs<S, I, J>(
+    start: S,
+    goal: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut predecessors: impl FnMut(&S) -> J,
+) -> SearchResult<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    J: IntoIterator<Item = S>,
+{
+    let mut stats: SearchStats = SearchStats {
+        visited: 0,
+        discovered: 1,
+    };
+    if start == goal {
+        return SearchResult {
+            path: Some(Path {
+                states: vec![start],
+                cost: 0,
+            }),
+            stats,
+        };
+    }
+    stats.discovered += 1;
+    let mut forward: Discovered<S> = Discovered::new(start);
+    let mut backward: Discovered<S> = Discovered::new(goal);
+    let mut forward_frontier: Vec<usize> = vec![0];
+    let mut backward_frontier: Vec<usize> = vec![0];
+
+    while !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        // Expand a whole layer of the smaller frontier.
+        let is_forward: bool = forward_frontier.len() <= backward_frontier.len();
+        let (this, other, frontier) = if is_forward {
+            (&mut forward, &backward, &mut forward_frontier)
+        } else {
+            (&mut backward, &forward, &mut backward_frontier)
+        };
+        let mut next_frontier: Vec<usize> = Vec::new();
+        // The shortest path may meet at any state of the layer, so finish it.
+        let mut meeting: Option<Vec<S>> = None;
+        for &index in frontier.iter() {
+            stats.visited += 1;
+            let state: S = this.states[index].clone();
+            let neighbours: Vec<S> = if is_forward {
+                successors(&state).into_iter().collect()
+            } else {
+                predecessors(&state).into_iter().collect()
+            };
+            for next in neighbours {
+                let (next_index, is_new) = this.insert(next, index);
+                if !is_new {
+                    continue;
+                }
+                stats.discovered += 1;
+                if let Some(&other_index) = other.indices.get(&this.states[next_index]) {
+                    let mut states: Vec<S> = this.path_to(next_index);
+                    let mut rest: Vec<S> = other.path_to(other_index);
+                    rest.pop();
+                    states.extend(rest.into_iter().rev());
+                    if !is_forward {
+                        states.reverse();
+                    }
+                    if meeting
+                        .as_ref()
+                        .is_none_or(|best| states.len() < best.len())
+                    {
+                        meeting = Some(states);
+                    }
+                }
+                next_frontier.push(next_index);
+            }
+        }
+        if let Some(states) = meeting {
+            let cost: usize = states.len() - 1;
+            return SearchResult {
+                path: Some(Path { states, cost }),
+                stats,
+            };
+        }
+        *frontier = next_frontier;
+    }
+    SearchResult { path: None, stats }
+}
+
+/// Successors on a grid: the orthogonal neighbours whose cell is `passable`.
+pub fn grid_successors<'a, T>(
+    grid: &'a Grid<T>,
+    passable: impl Fn(&T) -> bool + 'a,
+) -> impl Fn(&Position) -> Vec<Position> + 'a {
+    move |&pos| {
+        grid.neighbours4(pos)
+            .filter(|&next| passable(&grid[next]))
+            .collect()
+    }
+}
+
+/// Weighted successors on a grid: the orthogonal neighbours with the cost of
+/// entering their cell, or `None` when it is a wall.
+pub fn grid_weighted_successors<'a, T, C>(
+    grid: &'a Grid<T>,
+    cost: impl Fn(&T) -> Option<C> + 'a,
+) -> impl Fn(&Position) -> Vec<(Position, C)> + 'a {
+    move |&pos| {
+        grid.neighbours4(pos)
+            .filter_map(|next| Some((next, cost(&grid[next])?)))
+            .collect()
+    }
+}
+
+/// Weighted successors on a petgraph graph, following the edges out of a node,
+/// or all of its edges when the graph is undirected.
+pub fn graph_successors<'a, N, E, Ty, Ix, C>(
+    graph: &'a Graph<N, E, Ty, Ix>,
+    cost: impl Fn(&E) -> C + 'a,
+) -> impl Fn(&NodeIndex<Ix>) -> Vec<(NodeIndex<Ix>, C)> + 'a
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    move |&node| {
+        graph
+            .edges(node)
+            .map(|edge| (edge.target(), cost(edge.weight())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn maze() -> Grid<char> {
+        let lines: Vec<String> = ["S..#....", ".#.#.##.", ".#...#..", ".####.#.", "......#E"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        Grid::parse(&lines).unwrap()
+    }
+
+    #[test]
+    fn test_bfs_on_grid() {
+        let grid: Grid<char> = maze();
+        let goal: Position = grid.find(|&c| c == 'E').unwrap();
+        let result: SearchResult<Position, usize> =
+            bfs((0, 0), grid_successors(&grid, |&c| c != '#'), |&pos| {
+                pos == goal
+            });
+        let path: Path<Position, usize> = result.path.unwrap();
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.states.len(), 16);
+        assert_eq!(path.states.first(), Some(&(0, 0)));
+        assert_eq!(path.states.last(), Some(&goal));
+        assert!(
+            path.states
+                .windows(2)
+                .all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1)
+        );
+        assert!(result.stats.visited <= result.stats.discovered);
+
+        let distances: HashMap<Position, usize> =
+            bfs_distances((0, 0), grid_successors(&grid, |&c| c != '#'));
+        assert_eq!(distances[&goal], 15);
+        assert_eq!(distances.len(), grid.find_all(|&c| c != '#').len());
+        assert_eq!(
+            bfs((0, 0), grid_successors(&grid, |&c| c == '.'), |&pos| pos
+                == goal)
+            .path,
+            None
+        );
+    }
+
+    #[test]
+    fn test_weighted_search() {
+        let grid: Grid<u32> = Grid::parse_with(
+            &[
+                "1163751".to_string(),
+                "1381373".to_string(),
+                "2136511".to_string(),
+            ],
+            |c| Ok(c.to_digit(10).unwrap()),
+        )
+        .unwrap();
+        let goal: Position = (2, 6);
+        let successors = grid_weighted_successors(&grid, |&risk| Some(risk));
+        let by_dijkstra: SearchResult<Position, u32> =
+            dijkstra((0, 0), &successors, |&pos| pos == goal);
+        let by_astar: SearchResult<Position, u32> = astar(
+            (0, 0),
+            &successors,
+            |&(row, col)| (goal.0 - row + goal.1 - col) as u32,
+            |&pos| pos == goal,
+        );
+        assert_eq!(by_dijkstra.path.as_ref().unwrap().cost, 20);
+        assert_eq!(by_astar.path.as_ref().unwrap().cost, 20);
+        assert!(by_astar.stats.visited <= by_dijkstra.stats.visited);
+    }
+
+    #[test]
+    fn test_graph_search() {
+        let mut graph: Graph<&str, u32> = Graph::new();
+        let a: NodeIndex = graph.add_node("a");
+        let b: NodeIndex = graph.add_node("b");
+        let c: NodeIndex = graph.add_node("c");
+        let d: NodeIndex = graph.add_node("d");
+        graph.extend_with_edges([(a, b, 1), (b, d, 5), (a, c, 2), (c, d, 1), (d, a, 1)]);
+        let result: SearchResult<NodeIndex, u32> =
+            dijkstra(a, graph_successors(&graph, |&w| w), |&node| node == d);
+        assert_eq!(
+            result.path,
+            Some(Path {
+                states: vec![a, c, d],
+                cost: 3
+            })
+        );
+        assert_eq!(
+            dijkstra(d, graph_successors(&graph, |&w| w), |&node| node == c)
+                .path
+                .unwrap()
+                .cost,
+            3
+        );
+    }
+
+    #[test]
+    fn test_bidirectional_bfs() {
+        // Reach a number by doubling or adding one, and back by the inverse moves.
+        let successors = |&n: &u64| vec![n + 1, n * 2];
+        let predecessors = |&n: &u64| {
+            let mut previous: Vec<u64> = vec![n - 1];
+            if n % 2 == 0 {
+                previous.push(n / 2);
+            }
+            previous
+                .into_iter()
+                .filter(|&p| p >= 1)
+                .collect::<Vec<u64>>()
+        };
+        let result: SearchResult<u64, usize> = bidirectional_bfs(1, 100, successors, predecessors);
+        let expected: SearchResult<u64, usize> = bfs(1, successors, |&n| n == 100);
+        let path: Path<u64, usize> = result.path.unwrap();
+        assert_eq!(path.cost, expected.path.unwrap().cost);
+        assert_eq!(path.states.first(), Some(&1));
+        assert_eq!(path.states.last(), Some(&100));
+        assert_eq!(path.states.len(), path.cost + 1);
+        assert!(
+            path.states
+                .windows(2)
+                .all(|w| successors(&w[0]).contains(&w[1]))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_searches_agree(
+            cells in prop::collection::vec(prop::bool::weighted(0.7), 36),
+            goal in (0..6usize, 0..6usize),
+        ) {
+            let mut grid: Grid<bool> = Grid::from(ndarray::Array2::from_shape_vec((6, 6), cells).unwrap());
+            // Both ends must be open for the backward search to start from the goal.
+            grid[(0, 0)] = true;
+            grid[goal] = true;
+            let successors = grid_successors(&grid, |&open| open);
+            let by_bfs: Option<usize> = bfs((0, 0), &successors, |&pos| pos == goal).path.map(|p| p.cost);
+            let by_dijkstra: Option<usize> = dijkstra(
+                (0, 0),
+                |pos: &Position| successors(pos).into_iter().map(|next| (next, 1)),
+                |&pos| pos == goal,
+            )
+            .path
+            .map(|p| p.cost);
+            let by_bidirectional: Option<usize> =
+                bidirectional_bfs((0, 0), goal, &successors, &successors).path.map(|p| p.cost);
+            prop_assert_eq!(by_bfs, by_dijkstra);
+            prop_assert_eq!(by_bfs, by_bidirectional);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::{Error, Result};
+
+/// A named implementation of a puzzle part, returning the answer.
+pub struct Variant {
+    pub name: &'static str,
+    pub part: usize,
+    pub solve: fn(&[String]) -> Result<String, Error>,
+}
+
+/// Input lines on which variants of the same part disagree.
+#[derive(Debug)]
+pub struct Disagreement {
+    pub part: usize,
+    pub lines: Vec<String>,
+    /// Answer, or error message, of each variant.
+    pub answers: Vec<(&'static str, Result<String, String>)>,
+}
+
+/// Find the variant of a part by name.
+pub fn find_variant<'a>(
+    variants: &'a [Variant],
+    part: usize,
+    name: &str,
+) -> Result<&'a Variant, Error> {
+    variants
+        .iter()
+        .find(|v| v.part == part && v.name == name)
+        .ok_or(Error::msg(format!(
+            "Unknown variant '{}' for part {}. Available: {}.",
+            name,
+            part,
+            variants
+                .iter()
+                .filter(|v| v.part == part)
+                .map(|v| v.name)
+                .collect::<Vec<&str>>()
+                .join(", ")
+        )))
+}
+
+fn solve_all(
+    variants: &[&Variant],
+    lines: &[String],
+) -> Vec<(&'static str, Result<String, String>)> {
+    variants
+        .iter()
+        .map(|v| (v.name, (v.solve)(lines).map_err(|e| e.to_string())))
+        .collect()
+}
+
+fn is_disagreement(answers: &[(&'static str, Result<String, String>)]) -> bool {
+    answers.windows(2).any(|pair| pair[0].1 != pair[1].1)
+}
+
+/// Shrink `items` while `fails` holds, removing chunks of decreasing size, but never
+/// all of them.
+fn shrink<T: Clone>(mut items: Vec<T>, fails: impl Fn(&[T]) -> bool) -> Vec<T> {
+    let mut chunk_size: usize = items.len() / 2;
+    while chunk_size > 0 {
+        let mut start: usize = 0;
+        let mut removed_any: bool = false;
+        while start < items.len() {
+            let end: usize = (start + chunk_size).min(items.len());
+            let candidate: Vec<T> = [&items[..start], &items[end..]].concat();
+            if !candidate.is_empty() && fails(&candidate) {
+                items = candidate;
+                removed_any = true;
+            } else {
+                start = end;
+            }
+        }
+        if !removed_any {
+            chunk_size /= 2;
+        }
+    }
+    items
+}
+
+/// Shrink `lines` while `fails` holds: first whole lines, then within every line its
+/// comma-separated items, then its characters, so that single-line inputs shrink too.
+pub fn minimize(lines: Vec<String>, fails: impl Fn(&[String]) -> bool) -> Vec<String> {
+    let mut lines: Vec<String> = shrink(lines, &fails);
+    let replaced = |lines: &[String], index: usize, line: String| -> Vec<String> {
+        let mut candidate: Vec<String> = lines.to_vec();
+        candidate[index] = line;
+        candidate
+    };
+    for index in 0..lines.len() {
+        let items: Vec<String> = lines[index].split(',').map(str::to_string).collect();
+        let items: Vec<String> = shrink(items, |candidate| {
+            fails(&replaced(&lines, index, candidate.join(",")))
+        });
+        lines[index] = items.join(",");
+        let chars: Vec<char> = lines[index].chars().collect();
+        let chars: Vec<char> = shrink(chars, |candidate| {
+            fails(&replaced(&lines, index, candidate.iter().collect()))
+        });
+        lines[index] = chars.into_iter().collect();
+    }
+    lines
+}
+
+/// Run all the variants of every part on generated inputs of growing size.
+/// Returns the first disagreement, on a minimized input.
+pub fn find_disagreement(
+    variants: &[Variant],
+    generate: impl Fn(usize, u64) -> Vec<String>,
+    max_size: usize,
+    seeds: u64,
+) -> Option<Disagreement> {
+    let mut parts: Vec<usize> = variants.iter().map(|v| v.part).collect();
+    parts.sort();
+    parts.dedup();
+    for part in parts {
+        let part_variants: Vec<&Variant> = variants.iter().filter(|v| v.part == part).collect();
+        if part_variants.len() < 2 {
+            continue;
+        }
+        let mut size: usize = 1;
+        while size <= max_size {
+            for seed in 0..seeds {
+                let lines: Vec<String> = generate(size, seed);
+                let answers: Vec<(&'static str, Result<String, String>)> =
+                    solve_all(&part_variants, &lines);
+                if is_disagreement(&answers) {
+                    // Do not shrink a wrong answer into an input that every variant rejects.
+                    let has_answer: bool = answers.iter().any(|(_, answer)| answer.is_ok());
+                    let lines: Vec<String> = minimize(lines, |candidate| {
+                        let answers: Vec<(&'static str, Result<String, String>)> =
+                            solve_all(&part_variants, candidate);
+                        is_disagreement(&answers)
+                            && (!has_answer || answers.iter().any(|(_, answer)| answer.is_ok()))
+                    });
+                    return Some(Disagreement {
+                        part,
+                        answers: solve_all(&part_variants, &lines),
+                        lines,
+                    });
+                }
+            }
+            size *= 2;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn sum(lines: &[String]) -> Result<String, Error> {
+        Ok(lines
+            .iter()
+            .map(|l| l.parse::<usize>())
+            .sum::<Result<usize, _>>()?
+            .to_string())
+    }
+
+    fn buggy_sum(lines: &[String]) -> Result<String, Error> {
+        Ok(lines
+            .iter()
+            .map(|l| l.parse::<usize>().map(|v| v % 7))
+            .sum::<Result<usize, _>>()?
+            .to_string())
+    }
+
+    const VARIANTS: &[Variant] = &[
+        Variant {
+            name: "naive",
+            part: 1,
+            solve: sum,
+        },
+        Variant {
+            name: "fast",
+            part: 1,
+            solve: buggy_sum,
+        },
+    ];
+
+    fn generate(size: usize, seed: u64) -> Vec<String> {
+        (0..size as u64)
+            .map(|i| ((i * 3 + seed) % 9).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_minimize() {
+        let lines: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        assert_eq!(
+            minimize(lines, |candidate| candidate.contains(&"13".to_string())),
+            vec!["13".to_string()]
+        );
+        let line: Vec<String> = vec!["11-22,95-115,998-1012".to_string()];
+        assert_eq!(
+            minimize(line, |candidate| candidate[0].contains("95")),
+            vec!["95".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_find_disagreement() {
+        let disagreement: Disagreement = find_disagreement(VARIANTS, generate, 64, 3).unwrap();
+        assert_eq!(disagreement.part, 1);
+        assert_eq!(disagreement.lines.len(), 1);
+        assert!(disagreement.lines[0] == "7" || disagreement.lines[0] == "8");
+        assert!(find_disagreement(&VARIANTS[..1], generate, 64, 3).is_none());
+        assert!(find_variant(VARIANTS, 1, "fast").is_ok());
+        assert!(find_variant(VARIANTS, 2, "fast").is_err());
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use anyhow::{Error, Result};
+
+/// An assembly language: how to parse and execute its instructions.
+pub trait InstructionSet: Sized {
+    type Instruction: Clone + fmt::Debug + fmt::Display;
+
+    /// Number of registers of the machine.
+    const REGISTERS: usize;
+
+    /// Parse one line of source code.
+    fn parse(line: &str) -> Result<Self::Instruction, Error>;
+
+    /// Execute an instruction, and tell where to go next.
+    /// The whole machine is available, for instructions that rewrite the program.
+    fn execute(instruction: &Self::Instruction, machine: &mut Machine<Self>)
+    -> Result<Flow, Error>;
+}
+
+/// Where to go after an instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    /// The next instruction.
+    Next,
+    /// Jump by an offset relative to the current instruction.
+    Jump(i64),
+    /// Stop the program.
+    Halt,
+    /// Retry the same instruction once there is input.
+    Wait,
+}
+
+/// State of the machine after a step or a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The program goes on.
+    Running,
+    /// The program counter left the program, or the program halted.
+    Halted,
+    /// An instruction waits for input.
+    Waiting,
+    /// The program counter reached a breakpoint, which has not been executed yet.
+    Breakpoint(usize),
+    /// The step budget ran out.
+    StepLimit,
+}
+
+/// A loop found by the profiler: a backward jump and how often it was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HotLoop {
+    pub start: usize,
+    pub end: usize,
+    pub iterations: u64,
+}
+
+/// A register machine running a program of an instruction set.
+#[derive(Clone, Debug)]
+pub struct Machine<I: InstructionSet> {
+    pub program: Vec<I::Instruction>,
+    pub registers: Vec<i64>,
+    pub pc: i64,
+    /// Register bound to the program counter, like the `#ip` of elfcode.
+    pc_register: Option<usize>,
+    pub input: VecDeque<i64>,
+    pub output: Vec<i64>,
+    /// Instructions executed so far.
+    pub steps: u64,
+    breakpoints: HashSet<usize>,
+    counts: Vec<u64>,
+    back_jumps: HashMap<(usize, usize), u64>,
+}
+
+impl<I: InstructionSet> Machine<I> {
+    pub fn new(program: Vec<I::Instruction>) -> Machine<I> {
+        let counts: Vec<u64> = vec![0; program.len()];
+        Machine {
+            program,
+            registers: vec![0; I::REGISTERS],
+            pc: 0,
+            pc_register: None,
+            input: VecDeque::new(),
+            output: Vec::new(),
+            steps: 0,
+            breakpoints: HashSet::new(),
+            counts,
+            back_jumps: HashMap::new(),
+        }
+    }
+
+    /// Parse a program, one instruction per line.
+    pub fn parse(lines: &[String]) -> Result<Machine<I>, Error> {
+        let program: Vec<I::Instruction> = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                I::parse(line).map_err(|error| Error::msg(format!("Line {}: {}", index + 1, error)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Machine::new(program))
+    }
+
+    /// Bind a register to the program counter, like the `#ip` of elfcode.
+    pub fn bind_pc(&mut self, register: usize) -> Result<(), Error> {
+        if register >= I::REGISTERS {
+            return Err(Error::msg(format!(
+                "No register {}, the machine has {}.",
+                register,
+                I::REGISTERS
+            )));
+        }
+        self.pc_register = Some(register);
+        Ok(())
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    /// Index of the current instruction, if the program counter is within the program.
+    fn current(&self) -> Option<usize> {
+        usize::try_from(self.pc)
+            .ok()
+            .filter(|&pc| pc < self.program.len())
+    }
+
+    /// Execute a single instruction, ignoring breakpoints.
+    pub fn step(&mut self) -> Result<Status, Error> {
+        let Some(pc) = self.current() else {
+            return Ok(Status::Halted);
+        };
+        if let Some(register) = self.pc_register {
+            self.registers[register] = self.pc;
+        }
+        let instruction: I::Instruction = self.program[pc].clone();
+        let flow: Flow = I::execute(&instruction, self)?;
+        if flow == Flow::Wait {
+            return Ok(Status::Waiting);
+        }
+        tracing::trace!(
+            pc,
+            instruction = %instruction,
+            registers = ?self.registers,
+            "Executed"
+        );
+        self.steps += 1;
+        // The program is public and may have grown since the machine was built.
+        if self.counts.len() < self.program.len() {
+            self.counts.resize(self.program.len(), 0);
+        }
+        self.counts[pc] += 1;
+
+        let base: i64 = match self.pc_register {
+            Some(register) => self.registers[register],
+            None => self.pc,
+        };
+        let next: i64 = match flow {
+            Flow::Next | Flow::Wait => base.checked_add(1),
+            Flow::Jump(offset) => base.checked_add(offset),
+            Flow::Halt => return Ok(Status::Halted),
+        }
+        .ok_or(Error::msg(format!("Program counter overflow at {}.", pc)))?;
+        // Any move backwards closes a loop, whether by a jump or by writing the
+        // register bound to the program counter.
+        if next <= self.pc
+            && let Ok(target) = usize::try_from(next)
+        {
+            *self.back_jumps.entry((target, pc)).or_default() += 1;
+        }
+        self.pc = next;
+        Ok(if self.current().is_some() {
+            Status::Running
+        } else {
+            Status::Halted
+        })
+    }
+
+    /// Run until the program halts, waits for input or reaches a breakpoint.
+    /// The instruction at the program counter always runs, to resume from a breakpoint.
+    pub fn run(&mut self) -> Result<Status, Error> {
+        self.run_with_limit(u64::MAX)
+    }
+
+    /// Like `run`, but stopping after `max_steps` instructions.
+    pub fn run_with_limit(&mut self, max_steps: u64) -> Result<Status, Error> {
+        for step_index in 0..max_steps {
+            if step_index > 0
+                && let Some(pc) = self.current()
+                && self.breakpoints.contains(&pc)
+            {
+                return Ok(Status::Breakpoint(pc));
+            }
+            match self.step()? {
+                Status::Running => {}
+                status => return Ok(status),
+            }
+        }
+        Ok(Status::StepLimit)
+    }
+
+    /// Execution count of every instruction, missing for instructions appended to the
+    /// program after the last step.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The `n` most executed instructions, as `(pc, count)`.
+    pub fn hot_spots(&self, n: usize) -> Vec<(usize, u64)> {
+        let mut spots: Vec<(usize, u64)> = self.counts.iter().copied().enumerate().collect();
+        spots.sort_by_key(|&(pc, count)| (std::cmp::Reverse(count), pc));
+        spots.truncate(n);
+        spots
+    }
+
+    /// Loops closed by backward jumps, the most iterated first.
+    pub fn hot_loops(&self) -> Vec<HotLoop> {
+        let mut loops: Vec<HotLoop> = self
+            .back_jumps
+            .iter()
+            .map(|(&(start, end), &iterations)| HotLoop {
+                start,
+                end,
+                iterations,
+            })
+            .collect();
+        loops.sort_by_key(|l| (std::cmp::Reverse(l.iterations), l.start, l.end));
+        loops
+    }
+
+    /// The program with one instruction per line, its index, and `*` on breakpoints.
+    pub fn disassemble(&self) -> String {
+        self.program
+            .iter()
+            .enumerate()
+            .map(|(pc, instruction)| {
+                let marker: char = if self.breakpoints.contains(&pc) {
+                    '*'
+                } else {
+                    ' '
+                };
+                format!("{}{:>4}  {}", marker, pc, instruction)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// The program with execution counts, and the span of the hottest loop marked with `|`.
+    pub fn profile_report(&self) -> String {
+        let hottest: Option<HotLoop> = self.hot_loops().first().copied();
+        self.program
+            .iter()
+            .enumerate()
+            .map(|(pc, instruction)| {
+                let in_loop: bool = hottest.is_some_and(|l| (l.start..=l.end).contains(&pc));
+                format!(
+                    "{:>4} {:>12} {} {}",
+                    pc,
+                    self.counts.get(pc).copied().unwrap_or(0),
+                    if in_loop { '|' } else { ' ' },
+                    instruction
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// The computer of 2015 day 23, with registers `a` and `b`, and input/output.
+    #[derive(Clone, Debug)]
+    struct Turing;
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Hlf(usize),
+        Tpl(usize),
+        Inc(usize),
+        Jmp(i64),
+        Jie(usize, i64),
+        Jio(usize, i64),
+        Out(usize),
+        In(usize),
+    }
+
+    impl fmt::Display for Op {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let name = |r: &usize| ["a", "b"][*r];
+            match self {
+                Op::Hlf(r) => write!(f, "hlf {}", name(r)),
+                Op::Tpl(r) => write!(f, "tpl {}", name(r)),
+                Op::Inc(r) => write!(f, "inc {}", name(r)),
+                Op::Jmp(offset) => write!(f, "jmp {:+}", offset),
+                Op::Jie(r, offset) => write!(f, "jie {}, {:+}", name(r), offset),
+                Op::Jio(r, offset) => write!(f, "jio {}, {:+}", name(r), offset),
+                Op::Out(r) => write!(f, "out {}", name(r)),
+                Op::In(r) => write!(f, "in {}", name(r)),
+            }
+        }
+    }
+
+    impl InstructionSet for Turing {
+        type Instruction = Op;
+        const REGISTERS: usize = 2;
+
+        fn parse(line: &str) -> Result<Op, Error> {
+            let register = |r: &str| match r.trim_end_matches(',') {
+                "a" => Ok(0),
+                "b" => Ok(1),
+                _ => Err(Error::msg(format!("Unknown register '{}'.", r))),
+            };
+            let parts: Vec<&str> = line.split(' ').collect();
+            Ok(match parts[..] {
+                ["hlf", r] => Op::Hlf(register(r)?),
+                ["tpl", r] => Op::Tpl(register(r)?),
+                ["inc", r] => Op::Inc(register(r)?),
+                ["out", r] => Op::Out(register(r)?),
+                ["in", r] => Op::In(register(r)?),
+                ["jmp", offset] => Op::Jmp(offset.parse()?),
+                ["jie", r, offset] => Op::Jie(register(r)?, offset.parse()?),
+                ["jio", r, offset] => Op::Jio(register(r)?, offset.parse()?),
+                _ => return Err(Error::msg(format!("Unknown instruction '{}'.", line))),
+            })
+        }
+
+        fn execute(op: &Op, machine: &mut Machine<Turing>) -> Result<Flow, Error> {
+            let registers: &mut Vec<i64> = &mut machine.registers;
+            Ok(match *op {
+                Op::Hlf(r) => {
+                    registers[r] /= 2;
+                    Flow::Next
+                }
+                Op::Tpl(r) => {
+                    registers[r] *= 3;
+                    Flow::Next
+                }
+                Op::Inc(r) => {
+                    registers[r] += 1;
+                    Flow::Next
+                }
+                Op::Jmp(offset) => Flow::Jump(offset),
+                Op::Jie(r, offset) if registers[r] % 2 == 0 => Flow::Jump(offset),
+                Op::Jio(r, offset) if registers[r] == 1 => Flow::Jump(offset),
+                Op::Jie(..) | Op::Jio(..) => Flow::Next,
+                Op::Out(r) => {
+                    machine.output.push(registers[r]);
+                    Flow::Next
+                }
+                Op::In(r) => match machine.input.pop_front() {
+                    Some(value) => {
+                        machine.registers[r] = value;
+                        Flow::Next
+                    }
+                    None => Flow::Wait,
+                },
+            })
+        }
+    }
+
+    fn to_lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_run() -> Result<(), Error> {
+        let mut machine: Machine<Turing> =
+            Machine::parse(&to_lines(&["inc a", "jio a, +2", "tpl a", "inc a"]))?;
+        assert_eq!(machine.run()?, Status::Halted);
+        assert_eq!(machine.registers, vec![2, 0]);
+        assert_eq!(machine.steps, 3);
+        assert_eq!(
+            Machine::<Turing>::parse(&to_lines(&["inc a", "dec a"]))
+                .unwrap_err()
+                .to_string(),
+            "Line 2: Unknown instruction 'dec a'."
+        );
+
+        let mut jump: Machine<Turing> = Machine::parse(&to_lines(&["inc a", "jmp +1"]))?;
+        jump.program[1] = Op::Jmp(i64::MAX);
+        assert_eq!(
+            jump.run().unwrap_err().to_string(),
+            "Program counter overflow at 1."
+        );
+        assert!(jump.bind_pc(2).is_err());
+        assert!(jump.bind_pc(1).is_ok());
+
+        machine.program.push(Op::Inc(1));
+        assert!(machine.profile_report().ends_with("0   inc b"));
+        assert_eq!(machine.run()?, Status::Halted);
+        assert_eq!(machine.registers, vec![2, 1]);
+        assert_eq!(machine.counts(), &[1, 1, 0, 1, 1]);
+        Ok(())
+    }
+
+    /// Collatz steps from the input to 1, counted in `b` and written out.
+    fn collatz() -> Result<Machine<Turing>, Error> {
+        Machine::parse(&to_lines(&[
+            "in a",
+            "jio a, +8",
+            "inc b",
+            "jie a, +4",
+            "tpl a",
+            "inc a",
+            "jmp -5",
+            "hlf a",
+            "jmp -7",
+            "out b",
+        ]))
+    }
+
+    #[test]
+    fn test_input_and_breakpoints() -> Result<(), Error> {
+        let mut machine: Machine<Turing> = collatz()?;
+        assert_eq!(machine.run()?, Status::Waiting);
+        assert_eq!(machine.pc, 0);
+        machine.input.push_back(27);
+        machine.add_breakpoint(9);
+        assert_eq!(machine.run()?, Status::Breakpoint(9));
+        assert_eq!(machine.registers[1], 111);
+        assert!(machine.output.is_empty());
+        assert_eq!(machine.run()?, Status::Halted);
+        assert_eq!(machine.output, vec![111]);
+        assert!(
+            machine
+                .disassemble()
+                .lines()
+                .nth(9)
+                .unwrap()
+                .starts_with("*   9  out b")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_profiler() -> Result<(), Error> {
+        let mut machine: Machine<Turing> = collatz()?;
+        machine.input.push_back(27);
+        assert_eq!(machine.run_with_limit(10)?, Status::StepLimit);
+        assert_eq!(machine.run()?, Status::Halted);
+        assert_eq!(machine.counts()[2], 111);
+        assert_eq!(machine.hot_spots(1), vec![(1, 112)]);
+        let loops: Vec<HotLoop> = machine.hot_loops();
+        assert_eq!(
+            loops,
+            vec![
+                HotLoop {
+                    start: 1,
+                    end: 8,
+                    iterations: 70
+                },
+                HotLoop {
+                    start: 1,
+                    end: 6,
+                    iterations: 41
+                },
+            ]
+        );
+        assert!(
+            machine
+                .profile_report()
+                .lines()
+                .nth(8)
+                .unwrap()
+                .contains("| jmp -7")
+        );
+        assert!(
+            !machine
+                .profile_report()
+                .lines()
+                .nth(9)
+                .unwrap()
+                .contains('|')
+        );
+        Ok(())
+    }
+}